@@ -1,55 +1,31 @@
 use lzss::{IOSimpleReader, IOSimpleWriter, LzssDyn, LzssError, Read, Write};
 use std::cell::RefCell;
 use std::io::{stdin, stdout};
-use std::num::ParseIntError;
 use std::ops::AddAssign;
 use std::process::exit;
 use std::rc::Rc;
-use std::str::FromStr;
 
 // This is a very rudimentary program, everybody is welcome to improve it.
 
 #[derive(Debug)]
 struct Args {
     encode: bool,
-    ei: usize,
-    ej: usize,
-    c: u8,
+    lzss: LzssDyn,
 }
 
-fn parse_dec_or_hex_u8(i: &str) -> Result<u8, ParseIntError> {
-    if let Some(i) = i.strip_prefix("0x") {
-        u8::from_str_radix(i, 16)
-    } else {
-        u8::from_str(i)
-    }
-}
-
-fn parse_args() -> Result<Args, &'static str> {
+fn parse_args() -> Result<Args, String> {
     let args: Vec<_> = std::env::args().collect();
     if args.len() != 3 {
-        return Err("not exactly 2 arguments");
+        return Err("not exactly 2 arguments".to_string());
     }
     let encode = match args[1].as_str() {
         "e" => Ok(true),
         "d" => Ok(false),
         _ => Err("unknown command, use 'e' or 'd'"),
     }?;
-    let params: Vec<_> = args[2].split(',').collect();
-    if params.len() != 3 {
-        return Err("not exactly 3 compression parameters found");
-    }
-    let ei = params[0]
-        .trim()
-        .parse::<usize>()
-        .map_err(|_| "can't read ei")?;
-    let ej = params[1]
-        .trim()
-        .parse::<usize>()
-        .map_err(|_| "can't read ej")?;
-    let c = parse_dec_or_hex_u8(params[2].trim()).map_err(|_| "can't read c")?;
+    let lzss = args[2].parse::<LzssDyn>().map_err(|err| err.to_string())?;
 
-    Ok(Args { encode, ei, ej, c })
+    Ok(Args { encode, lzss })
 }
 
 struct ReadCounter<T>(T, Rc<RefCell<usize>>);
@@ -91,14 +67,11 @@ fn main() {
     let args = parse_args().unwrap_or_else(|err| {
         let name = std::env::args().next().unwrap();
         eprintln!("error: {err}");
-        eprintln!("usage: {name} <'e'|'d'> <ei,ej,c>");
+        eprintln!("usage: {name} <'e'|'d'> <ei,ej,c[,p][,option...]>");
         eprintln!("example: {name} e 10,4,0x20");
         exit(1)
     });
-    let lzss = LzssDyn::new(args.ei, args.ej, args.c).unwrap_or_else(|err| {
-        eprintln!("error: {err}");
-        exit(1)
-    });
+    let lzss = args.lzss;
     let mut stdin = stdin();
     let mut stdout = stdout();
     let i_cnt = Rc::new(RefCell::new(0));
@@ -24,6 +24,29 @@ pub fn criterion_benchmark(c: &mut Criterion) {
             BatchSize::SmallInput,
         )
     });
+    c.bench_function("compress generic ring example", |b| {
+        b.iter_batched(
+            || {
+                (
+                    SliceReader::new(EXAMPLE_DATA),
+                    VecWriter::with_capacity(EXAMPLE_DATA.len()),
+                    vec![0; 1 << 10],
+                )
+            },
+            |(r, w, mut b)| {
+                MyLzss::compress_ring_with_buffer(r, w, (&mut b[..]).try_into().unwrap())
+                    .void_unwrap()
+            },
+            BatchSize::SmallInput,
+        )
+    });
+    c.bench_function("compress generic slice example", |b| {
+        b.iter_batched(
+            || VecWriter::with_capacity(EXAMPLE_DATA.len()),
+            |w| MyLzss::compress_slice(black_box(EXAMPLE_DATA), w).void_unwrap(),
+            BatchSize::SmallInput,
+        )
+    });
     c.bench_function("compress generic in-place example", |b| {
         b.iter_batched(
             || {
@@ -3,7 +3,7 @@ use std::fs::File;
 use std::io::{Error, ErrorKind, Write};
 use std::path::{Path, PathBuf};
 
-fn run_template(path: PathBuf, source: &str, buffer: &str) -> Result<(), Error> {
+fn run_template(path: PathBuf, source: &str, buffer: Option<&str>) -> Result<(), Error> {
     let mut file = File::create(path)?;
 
     println!("cargo:rerun-if-changed={source}");
@@ -13,16 +13,39 @@ fn run_template(path: PathBuf, source: &str, buffer: &str) -> Result<(), Error>
             .replace("crate::dynamic::LzssDyn", "crate::generic::Lzss")
             .replace(
                 "impl LzssDyn {",
-                "impl<const EI: usize, const EJ: usize, const C: u8, const N: usize, const N2: usize> Lzss<EI, EJ, C, N, N2> {",
+                "impl<const EI: usize, const EJ: usize, const C: u8, const N: usize, const N2: usize, const P: usize, const O: u8> Lzss<EI, EJ, C, N, N2, P, O> {",
             )
             .replace("&self,", "")
-            .replace("buffer: &mut [u8],", buffer)
+            .replace("buffer: &mut [u8],", buffer.unwrap_or("buffer: &mut [u8],"))
+            .replace("buffer: &[u8],", &buffer.unwrap_or("buffer: &mut [u8],").replace("&mut ", "&"))
             .replace("self.ei", "EI")
             .replace("self.ej", "EJ")
             .replace("self.f()", "Self::F")
             .replace("self.n()", "N")
             .replace("self.p()", "Self::P")
+            .replace("self.c()", "C")
+            .replace("self.options()", "Self::OPTIONS")
+            .replace("self.min_gap_size()", "Self::MIN_GAP_SIZE")
+            .replace("self.min_offset()", "Self::MIN_OFFSET")
+            .replace("self.offset_bits(", "Self::offset_bits(")
+            .replace("self.write_match(", "Self::write_match(")
+            .replace("self.write_marker(", "Self::write_marker(")
+            .replace("self.read_token(", "Self::read_token(")
+            .replace("self.is_marker(", "Self::is_marker(")
+            .replace("self.distance(", "Self::distance(")
+            .replace("self.read_length(", "Self::read_length(")
+            .replace("self.compress_internal(", "Self::compress_internal(")
+            .replace("self.decompress_window_internal(", "Self::decompress_window_internal(")
+            .replace("self.compress_window_internal(", "Self::compress_window_internal(")
             ;
+        if l.split(|c: char| !c.is_alphanumeric() && c != '_')
+            .any(|word| word == "self")
+        {
+            return Err(Error::new(
+                ErrorKind::Other,
+                format!("{source}: no replacement for `self` in: {l}"),
+            ));
+        }
         l.push('\n');
         file.write_all(l.as_bytes())?;
     }
@@ -38,13 +61,37 @@ fn main() -> Result<(), Error> {
     run_template(
         out_dir.join("generic-compress.rs"),
         "src/dynamic/compress.rs",
-        "buffer: &mut [u8; N2],",
+        Some("buffer: &mut [u8; N2],"),
+    )?;
+
+    run_template(
+        out_dir.join("generic-compress_ring.rs"),
+        "src/dynamic/compress_ring.rs",
+        Some("buffer: &mut [u8; N],"),
+    )?;
+
+    run_template(
+        out_dir.join("generic-compress_slice.rs"),
+        "src/dynamic/compress_slice.rs",
+        None,
     )?;
 
     run_template(
         out_dir.join("generic-decompress.rs"),
         "src/dynamic/decompress.rs",
-        "buffer: &mut [u8; N],",
+        Some("buffer: &mut [u8; N],"),
+    )?;
+
+    run_template(
+        out_dir.join("generic-compress_in_place.rs"),
+        "src/dynamic/compress_in_place.rs",
+        Some("buffer: &mut [u8; N2],"),
+    )?;
+
+    run_template(
+        out_dir.join("generic-decompress_slice.rs"),
+        "src/dynamic/decompress_slice.rs",
+        None,
     )?;
 
     Ok(())
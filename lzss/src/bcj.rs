@@ -0,0 +1,426 @@
+use crate::read_write::{Read, Write};
+
+/// The instruction set of a branch-conversion (BCJ) filter, see [`BcjReader`] and [`BcjWriter`].
+///
+/// The filters convert the relative addresses of calls and jumps into absolute addresses,
+/// thus all calls to the same function are identical and can be compressed much better.
+/// The conversion is reversible for any data, it does not have to be an executable.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum BcjFilter {
+    /// x86 (32- and 64-bit) `CALL` and `JMP` with a 32-bit displacement (`E8`/`E9`).
+    ///
+    /// Only displacements within ±16 MiB are converted.
+    X86,
+    /// ARM Thumb and Thumb-2 `BL` (e.g. Cortex-M), with 2-byte aligned instructions.
+    ArmThumb,
+}
+
+impl BcjFilter {
+    // The length of an instruction, thus the number of bytes which are required to decide.
+    #[inline(always)]
+    const fn size(self) -> usize {
+        match self {
+            BcjFilter::X86 => 5,
+            BcjFilter::ArmThumb => 4,
+        }
+    }
+}
+
+// The common state of the reader and writer: a window with the next few bytes, the `ready`
+// bytes in front are already converted (or skipped). The next `skip` bytes are never
+// converted (x86 only).
+struct BcjState {
+    filter: BcjFilter,
+    encode: bool,
+    position: u32,
+    buffer: [u8; 5],
+    len: usize,
+    ready: usize,
+    skip: u8,
+}
+
+impl BcjState {
+    #[inline(always)]
+    fn new(filter: BcjFilter, encode: bool) -> Self {
+        BcjState {
+            filter,
+            encode,
+            position: 0,
+            buffer: [0; 5],
+            len: 0,
+            ready: 0,
+            skip: 0,
+        }
+    }
+
+    #[inline(always)]
+    fn is_full(&self) -> bool {
+        self.len == self.filter.size()
+    }
+
+    #[inline(always)]
+    fn push(&mut self, data: u8) {
+        self.buffer[self.len] = data;
+        self.len += 1;
+    }
+
+    #[inline(always)]
+    fn pop(&mut self) -> u8 {
+        let data = self.buffer[0];
+        self.buffer.copy_within(1..self.len, 0);
+        self.len -= 1;
+        self.ready -= 1;
+        self.position = self.position.wrapping_add(1);
+        data
+    }
+
+    // Convert the instruction in the full window, or skip a byte (x86) or an instruction (ARM).
+    fn convert(&mut self) {
+        let b = &mut self.buffer;
+        match self.filter {
+            BcjFilter::X86 => {
+                // The displacement must be a sign extended 25 bit number, it's converted into
+                // one again (the top byte is either 0x00 or 0xff), thus the decoder finds it too.
+                // When an opcode is not converted, the next three bytes are skipped: a
+                // conversion there would change the top byte which was checked, and the
+                // decoder would decide differently.
+                if self.skip > 0 {
+                    self.skip -= 1;
+                    self.ready = 1;
+                } else if (b[0] == 0xe8 || b[0] == 0xe9) && (b[4] == 0x00 || b[4] == 0xff) {
+                    let src = u32::from_le_bytes([b[1], b[2], b[3], b[4]]);
+                    let next = self.position.wrapping_add(5);
+                    let dest = if self.encode {
+                        src.wrapping_add(next)
+                    } else {
+                        src.wrapping_sub(next)
+                    };
+                    let dest = (dest & 0x01ff_ffff) | (0u32.wrapping_sub(dest >> 24 & 1) << 25);
+                    b[1..5].copy_from_slice(&dest.to_le_bytes());
+                    self.ready = 5;
+                } else {
+                    if b[0] == 0xe8 || b[0] == 0xe9 {
+                        self.skip = 3;
+                    }
+                    self.ready = 1;
+                }
+            }
+            BcjFilter::ArmThumb => {
+                // Two half-words: 11110 with the upper and 11111 with the lower 11 bits.
+                if (b[1] & 0xf8) == 0xf0 && (b[3] & 0xf8) == 0xf8 {
+                    let src = (u32::from(b[1] & 7) << 19)
+                        | (u32::from(b[0]) << 11)
+                        | (u32::from(b[3] & 7) << 8)
+                        | u32::from(b[2]);
+                    let next = self.position.wrapping_add(4) >> 1;
+                    let dest = if self.encode {
+                        src.wrapping_add(next)
+                    } else {
+                        src.wrapping_sub(next)
+                    };
+                    b[0] = (dest >> 11) as u8;
+                    b[1] = 0xf0 | ((dest >> 19) & 7) as u8;
+                    b[2] = dest as u8;
+                    b[3] = 0xf8 | ((dest >> 8) & 7) as u8;
+                    self.ready = 4;
+                } else {
+                    self.ready = 2;
+                }
+            }
+        }
+    }
+}
+
+/// A reader which converts the data of the underlying reader with a [`BcjFilter`].
+///
+/// Use [`BcjReader::encoder`] in front of a compression and [`BcjReader::decoder`] to read
+/// from something which emits the decompressed data (or use [`BcjWriter`]).
+///
+/// The position for the conversion is the number of bytes read, i.e. the data starts at address 0.
+///
+/// # Example
+/// ```rust
+/// # use lzss::{BcjFilter, BcjReader, BcjWriter, LzssDyn, LzssDynError, ResultLzssErrorVoidExt, SliceReader, VecWriter};
+/// let my_lzss = LzssDyn::new(10, 4, 0x20)?;
+/// // two calls to the same function (at 0x100)
+/// let input = [0x90, 0xe8, 0xfa, 0x00, 0x00, 0x00, 0xe8, 0xf5, 0x00, 0x00, 0x00];
+/// let compressed = my_lzss
+///     .compress(
+///         BcjReader::encoder(BcjFilter::X86, SliceReader::new(&input)),
+///         VecWriter::with_capacity(30),
+///     )
+///     .void_unwrap();
+/// let output = my_lzss
+///     .decompress(
+///         SliceReader::new(&compressed),
+///         BcjWriter::decoder(BcjFilter::X86, VecWriter::with_capacity(30)),
+///     )
+///     .void_unwrap();
+/// assert_eq!(output, input);
+/// # Ok::<(), LzssDynError>(())
+/// ```
+pub struct BcjReader<R> {
+    reader: R,
+    state: BcjState,
+}
+
+impl<R: Read> BcjReader<R> {
+    /// Create a reader which converts the relative addresses into absolute ones.
+    #[inline(always)]
+    #[must_use]
+    pub fn encoder(filter: BcjFilter, reader: R) -> Self {
+        BcjReader {
+            reader,
+            state: BcjState::new(filter, true),
+        }
+    }
+
+    /// Create a reader which converts the absolute addresses back into relative ones.
+    #[inline(always)]
+    #[must_use]
+    pub fn decoder(filter: BcjFilter, reader: R) -> Self {
+        BcjReader {
+            reader,
+            state: BcjState::new(filter, false),
+        }
+    }
+}
+
+impl<R: Read> Read for BcjReader<R> {
+    /// The error of the underlying reader.
+    type Error = R::Error;
+    fn read(&mut self) -> Result<Option<u8>, Self::Error> {
+        if self.state.ready == 0 {
+            while !self.state.is_full() {
+                match self.reader.read()? {
+                    None => break,
+                    Some(data) => self.state.push(data),
+                }
+            }
+            if self.state.is_full() {
+                self.state.convert();
+            } else if self.state.len == 0 {
+                return Ok(None);
+            } else {
+                // an incomplete instruction at the end is not converted
+                self.state.ready = self.state.len;
+            }
+        }
+        Ok(Some(self.state.pop()))
+    }
+}
+
+/// A writer which converts the data with a [`BcjFilter`] before writing it into the underlying writer.
+///
+/// Use [`BcjWriter::decoder`] as the output of a decompression and [`BcjWriter::encoder`] to
+/// convert data for something which receives the data (or use [`BcjReader`]).
+///
+/// The position for the conversion is the number of bytes written, i.e. the data starts at address 0.
+/// The last few bytes are only written when [`Write::finish`] is called.
+pub struct BcjWriter<W> {
+    writer: W,
+    state: BcjState,
+}
+
+impl<W: Write> BcjWriter<W> {
+    /// Create a writer which converts the relative addresses into absolute ones.
+    #[inline(always)]
+    #[must_use]
+    pub fn encoder(filter: BcjFilter, writer: W) -> Self {
+        BcjWriter {
+            writer,
+            state: BcjState::new(filter, true),
+        }
+    }
+
+    /// Create a writer which converts the absolute addresses back into relative ones.
+    #[inline(always)]
+    #[must_use]
+    pub fn decoder(filter: BcjFilter, writer: W) -> Self {
+        BcjWriter {
+            writer,
+            state: BcjState::new(filter, false),
+        }
+    }
+}
+
+impl<W: Write> Write for BcjWriter<W> {
+    /// The output of the underlying writer.
+    type Output = W::Output;
+    /// The error of the underlying writer.
+    type Error = W::Error;
+    fn write(&mut self, data: u8) -> Result<(), Self::Error> {
+        self.state.push(data);
+        if self.state.is_full() {
+            self.state.convert();
+            while self.state.ready > 0 {
+                self.writer.write(self.state.pop())?;
+            }
+        }
+        Ok(())
+    }
+    fn finish(mut self) -> Result<Self::Output, Self::Error> {
+        // an incomplete instruction at the end is not converted
+        self.state.ready = self.state.len;
+        while self.state.ready > 0 {
+            self.writer.write(self.state.pop())?;
+        }
+        self.writer.finish()
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use crate::bcj::{BcjFilter, BcjReader, BcjWriter};
+    use crate::read_write::{Read, Write};
+    use crate::slice::SliceReader;
+    use crate::vec::VecWriter;
+    #[cfg(not(feature = "std"))]
+    use alloc::vec::Vec;
+    use void::ResultVoidExt;
+
+    fn read_all<R: Read<Error = void::Void>>(mut reader: R) -> Vec<u8> {
+        let mut result = Vec::new();
+        while let Some(data) = reader.read().void_unwrap() {
+            result.push(data);
+        }
+        result
+    }
+
+    fn write_all<W: Write<Output = Vec<u8>, Error = void::Void>>(
+        mut writer: W,
+        data: &[u8],
+    ) -> Vec<u8> {
+        for &b in data {
+            writer.write(b).void_unwrap();
+        }
+        writer.finish().void_unwrap()
+    }
+
+    #[test]
+    fn test_x86() {
+        // calls to 0x1000 from different positions, a jump, a far call and a truncated call
+        let mut input = Vec::new();
+        for i in 0..20 {
+            input.extend_from_slice(&[0x90; 3][..i % 3]);
+            let next = input.len() as u32 + 5;
+            input.push(0xe8);
+            input.extend_from_slice(&(0x1000 - next).to_le_bytes());
+        }
+        input.extend_from_slice(&[0xe9, 0x00, 0xf0, 0xff, 0xff, 0xe8, 0, 0, 0, 0x12]);
+        input.extend_from_slice(&[0xe8, 0x00, 0x10]);
+        let encoded = read_all(BcjReader::encoder(BcjFilter::X86, SliceReader::new(&input)));
+        assert_eq!(
+            encoded,
+            write_all(
+                BcjWriter::encoder(BcjFilter::X86, VecWriter::with_capacity(0)),
+                &input
+            )
+        );
+        assert_eq!(encoded.len(), input.len());
+        assert_eq!(encoded[..5], [0xe8, 0x00, 0x10, 0x00, 0x00]);
+        assert_eq!(encoded[6..11], [0xe8, 0x00, 0x10, 0x00, 0x00]);
+        assert_eq!(
+            input,
+            read_all(BcjReader::decoder(
+                BcjFilter::X86,
+                SliceReader::new(&encoded)
+            ))
+        );
+        assert_eq!(
+            input,
+            write_all(
+                BcjWriter::decoder(BcjFilter::X86, VecWriter::with_capacity(0)),
+                &encoded
+            )
+        );
+    }
+
+    #[test]
+    fn test_x86_overlapping() {
+        // opcodes within the displacement of other opcodes, and adjacent calls
+        let inputs: [&[u8]; 5] = [
+            &[0xe8, 0xe8, 0xfa, 0xff, 0xfe, 0x00],
+            &[0xe9, 0xe8, 0xe8, 0xe9, 0x12, 0x00, 0xff, 0x00],
+            &[0xe8, 0x00, 0x00, 0x00, 0x00, 0xe9, 0x10, 0x00, 0x00, 0xff],
+            &[
+                0xe8, 0xe8, 0x00, 0x00, 0x00, 0x00, 0xe8, 0xff, 0xff, 0xff, 0xff,
+            ],
+            &[
+                0x00, 0xe8, 0x01, 0xe8, 0x02, 0xe9, 0x00, 0xff, 0x00, 0x00, 0xff,
+            ],
+        ];
+        let mut random = Vec::new();
+        let mut x: u32 = 1;
+        for _ in 0..10000 {
+            x = x.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            random.push([0x00, 0xff, 0xe8, 0xe9, 0x12][(x >> 16) as usize % 5]);
+        }
+        for input in inputs.iter().copied().chain([random.as_slice()]) {
+            let encoded = read_all(BcjReader::encoder(BcjFilter::X86, SliceReader::new(input)));
+            assert_eq!(
+                encoded,
+                write_all(
+                    BcjWriter::encoder(BcjFilter::X86, VecWriter::with_capacity(0)),
+                    input
+                )
+            );
+            assert_eq!(
+                input,
+                read_all(BcjReader::decoder(
+                    BcjFilter::X86,
+                    SliceReader::new(&encoded)
+                ))
+            );
+            assert_eq!(
+                input,
+                write_all(
+                    BcjWriter::decoder(BcjFilter::X86, VecWriter::with_capacity(0)),
+                    &encoded
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_arm_thumb() {
+        // calls to 0x1000 from different positions, and a truncated call
+        let mut input = Vec::new();
+        for i in 0..20 {
+            input.extend_from_slice(&[0x00, 0xbf, 0x00, 0xbf][..(i % 3) * 2]);
+            let offset = (0x1000 - (input.len() as u32 + 4)) >> 1;
+            input.extend_from_slice(&[
+                (offset >> 11) as u8,
+                0xf0 | ((offset >> 19) & 7) as u8,
+                offset as u8,
+                0xf8 | ((offset >> 8) & 7) as u8,
+            ]);
+        }
+        input.extend_from_slice(&[0x00, 0xf0, 0x00]);
+        let encoded = read_all(BcjReader::encoder(
+            BcjFilter::ArmThumb,
+            SliceReader::new(&input),
+        ));
+        assert_eq!(encoded[..4], [0x01, 0xf0, 0x00, 0xf8]);
+        assert_eq!(encoded[6..10], [0x01, 0xf0, 0x00, 0xf8]);
+        assert_eq!(
+            input,
+            write_all(
+                BcjWriter::decoder(BcjFilter::ArmThumb, VecWriter::with_capacity(0)),
+                &encoded
+            )
+        );
+    }
+
+    #[test]
+    fn test_any_data() {
+        let input = include_bytes!("bcj.rs");
+        for filter in [BcjFilter::X86, BcjFilter::ArmThumb] {
+            let encoded = read_all(BcjReader::encoder(filter, SliceReader::new(input)));
+            assert_eq!(
+                read_all(BcjReader::decoder(filter, SliceReader::new(&encoded))),
+                input
+            );
+        }
+    }
+}
@@ -18,7 +18,7 @@ impl<R: Read> BitReader<'_, R> {
 
     #[inline(always)]
     pub(crate) fn read_bits(&mut self, len: usize) -> Result<Option<u32>, R::Error> {
-        let len = len as u8; // len is 24 at most anyway
+        let len = len as u8; // len is 25 at most (1 + EI + EJ), plus less than 8 bits in buf
         while self.bits_in_buf < len {
             if let Some(val) = self.reader.read()? {
                 self.buf = (self.buf << 8) | (val as u32);
@@ -30,6 +30,12 @@ impl<R: Read> BitReader<'_, R> {
         self.bits_in_buf -= len;
         Ok(Some((self.buf >> self.bits_in_buf) & ((1 << len) - 1)))
     }
+
+    /// Skip the remaining bits of the current byte.
+    #[inline(always)]
+    pub(crate) fn align(&mut self) {
+        self.bits_in_buf = 0;
+    }
 }
 
 pub(crate) struct BitWriter<'a, W> {
@@ -48,9 +54,19 @@ impl<W: Write> BitWriter<'_, W> {
         }
     }
 
+    /// Create a writer which already contains `bits_in_buf` bits (from the lsb of `buf`).
+    #[inline(always)]
+    pub(crate) fn with_bits(writer: &mut W, buf: u32, bits_in_buf: u8) -> BitWriter<'_, W> {
+        BitWriter {
+            bits_in_buf,
+            buf,
+            writer,
+        }
+    }
+
     #[inline(always)]
     pub(crate) fn write_bits(&mut self, data: u32, len: usize) -> Result<(), W::Error> {
-        let len = len as u8; // len is 24 at most anyway
+        let len = len as u8; // len is 25 at most (1 + EI + EJ), plus less than 8 bits in buf
         self.buf = (self.buf << len) | data;
         self.bits_in_buf += len;
 
@@ -62,6 +78,12 @@ impl<W: Write> BitWriter<'_, W> {
         Ok(())
     }
 
+    /// Get the buffered bits, to continue later with [`BitWriter::with_bits`].
+    #[inline(always)]
+    pub(crate) fn bits(&self) -> (u32, u8) {
+        (self.buf, self.bits_in_buf)
+    }
+
     #[inline(always)]
     pub(crate) fn flush(&mut self) -> Result<(), W::Error> {
         if self.bits_in_buf > 0 {
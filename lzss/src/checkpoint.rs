@@ -0,0 +1,114 @@
+use crate::dynamic::LzssDyn;
+use crate::read_write::{Read, Write};
+use core::fmt::Display;
+
+// A checkpoint starts with the kind and the parameters, followed by the state (see
+// LzssCompressor::save and LzssDecompressor::save), all numbers are little-endian.
+
+pub(crate) const KIND_COMPRESSOR: u8 = b'C';
+pub(crate) const KIND_DECOMPRESSOR: u8 = b'D';
+
+/// The error returned when restoring a checkpoint of [`LzssCompressor`](crate::LzssCompressor)
+/// or [`LzssDecompressor`](crate::LzssDecompressor).
+#[derive(Debug, Eq, PartialEq)]
+pub enum LzssCheckpointError<R> {
+    /// Contains the read error value.
+    ReadError(R),
+    /// The checkpoint is truncated, corrupt, of the other kind or for other parameters.
+    Invalid,
+}
+
+impl<R: Display> core::fmt::Display for LzssCheckpointError<R> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            LzssCheckpointError::ReadError(error) => write!(f, "Read error: {error}"),
+            LzssCheckpointError::Invalid => f.write_str("Invalid checkpoint"),
+        }
+    }
+}
+
+/// Implementation of [`Error`](std::error::Error) for [`LzssCheckpointError`]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[cfg(feature = "std")]
+impl<R> std::error::Error for LzssCheckpointError<R>
+where
+    R: std::error::Error + 'static,
+{
+    #[inline]
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LzssCheckpointError::ReadError(error) => Some(error),
+            LzssCheckpointError::Invalid => None,
+        }
+    }
+}
+
+#[inline(always)]
+pub(crate) fn write_u32<W: Write>(writer: &mut W, value: usize) -> Result<(), W::Error> {
+    for b in (value as u32).to_le_bytes() {
+        writer.write(b)?;
+    }
+    Ok(())
+}
+
+#[inline(always)]
+pub(crate) fn write_header<W: Write>(
+    writer: &mut W,
+    kind: u8,
+    lzss: &LzssDyn,
+) -> Result<(), W::Error> {
+    writer.write(kind)?;
+    writer.write(lzss.ei() as u8)?;
+    writer.write(lzss.ej() as u8)?;
+    writer.write(lzss.c())?;
+    write_u32(writer, lzss.p())?;
+    writer.write(lzss.options().bits())
+}
+
+#[inline(always)]
+pub(crate) fn read_u8<R: Read>(reader: &mut R) -> Result<u8, LzssCheckpointError<R::Error>> {
+    reader
+        .read()
+        .map_err(LzssCheckpointError::ReadError)?
+        .ok_or(LzssCheckpointError::Invalid)
+}
+
+#[inline(always)]
+pub(crate) fn read_u32<R: Read>(reader: &mut R) -> Result<usize, LzssCheckpointError<R::Error>> {
+    let mut value = [0; 4];
+    for b in &mut value {
+        *b = read_u8(reader)?;
+    }
+    Ok(u32::from_le_bytes(value) as usize)
+}
+
+#[inline(always)]
+pub(crate) fn read_into<R: Read>(
+    reader: &mut R,
+    data: &mut [u8],
+) -> Result<(), LzssCheckpointError<R::Error>> {
+    for b in data {
+        *b = read_u8(reader)?;
+    }
+    Ok(())
+}
+
+// Check that the kind and parameters are the expected ones.
+#[inline(always)]
+pub(crate) fn read_header<R: Read>(
+    reader: &mut R,
+    kind: u8,
+    lzss: &LzssDyn,
+) -> Result<(), LzssCheckpointError<R::Error>> {
+    if read_u8(reader)? == kind
+        && read_u8(reader)? == lzss.ei() as u8
+        && read_u8(reader)? == lzss.ej() as u8
+        && read_u8(reader)? == lzss.c()
+        && read_u32(reader)? == lzss.p()
+        && read_u8(reader)? == lzss.options().bits()
+    {
+        Ok(())
+    } else {
+        Err(LzssCheckpointError::Invalid)
+    }
+}
@@ -0,0 +1,307 @@
+use crate::dynamic::LzssDyn;
+use crate::error::LzssError;
+use crate::flag_byte::LzssFlagByte;
+use crate::generic::Lzss;
+use crate::read_write::{Read, Write};
+
+/// Common interface of [`Lzss`], [`LzssDyn`] and [`LzssFlagByte`].
+///
+/// This allows to write code once which accepts either compile-time or runtime parameters.
+///
+/// ```rust
+/// # use lzss::{Lzss, LzssCodec, LzssDyn, LzssDynError, ResultLzssErrorVoidExt, SliceReader, VecWriter};
+/// fn compressed_size<L: LzssCodec>(lzss: &L, input: &[u8]) -> usize {
+///     let mut buffer = vec![0; lzss.compress_buffer_size()];
+///     lzss.compress_with_buffer(
+///         SliceReader::new(input),
+///         VecWriter::with_capacity(input.len()),
+///         &mut buffer,
+///     )
+///     .void_unwrap()
+///     .len()
+/// }
+///
+/// type MyLzss = Lzss<10, 4, 0x20, { 1 << 10 }, { 2 << 10 }>;
+/// assert_eq!(compressed_size(&MyLzss::new(), b"Example Data"), 14);
+/// assert_eq!(compressed_size(&LzssDyn::new(10, 4, 0x20)?, b"Example Data"), 14);
+/// # Ok::<(), LzssDynError>(())
+/// ```
+pub trait LzssCodec {
+    /// The minimal size of the buffer for [`compress_with_buffer`](LzssCodec::compress_with_buffer).
+    fn compress_buffer_size(&self) -> usize;
+
+    /// The minimal size of the buffer for [`decompress_with_buffer`](LzssCodec::decompress_with_buffer).
+    fn decompress_buffer_size(&self) -> usize;
+
+    /// Compress the input data into the output.
+    ///
+    /// The buffer is allocated on the heap.
+    #[cfg_attr(docsrs, doc(cfg(any(feature = "alloc", feature = "std"))))]
+    #[cfg(feature = "alloc")]
+    fn compress<R: Read, W: Write>(
+        &self,
+        reader: R,
+        writer: W,
+    ) -> Result<W::Output, LzssError<R::Error, W::Error>>;
+
+    /// Compress the input data into the output.
+    ///
+    /// It will be asserted at runtime that the buffer is at least [`compress_buffer_size`](LzssCodec::compress_buffer_size).
+    fn compress_with_buffer<R: Read, W: Write>(
+        &self,
+        reader: R,
+        writer: W,
+        buffer: &mut [u8],
+    ) -> Result<W::Output, LzssError<R::Error, W::Error>>;
+
+    /// Decompress the input data into the output.
+    ///
+    /// The buffer is allocated on the heap.
+    #[cfg_attr(docsrs, doc(cfg(any(feature = "alloc", feature = "std"))))]
+    #[cfg(feature = "alloc")]
+    fn decompress<R: Read, W: Write>(
+        &self,
+        reader: R,
+        writer: W,
+    ) -> Result<W::Output, LzssError<R::Error, W::Error>>;
+
+    /// Decompress the input data into the output.
+    ///
+    /// It will be asserted at runtime that the buffer is at least [`decompress_buffer_size`](LzssCodec::decompress_buffer_size).
+    fn decompress_with_buffer<R: Read, W: Write>(
+        &self,
+        reader: R,
+        writer: W,
+        buffer: &mut [u8],
+    ) -> Result<W::Output, LzssError<R::Error, W::Error>>;
+}
+
+impl<
+        const EI: usize,
+        const EJ: usize,
+        const C: u8,
+        const N: usize,
+        const N2: usize,
+        const P: usize,
+        const O: u8,
+    > LzssCodec for Lzss<EI, EJ, C, N, N2, P, O>
+{
+    #[inline(always)]
+    fn compress_buffer_size(&self) -> usize {
+        N2
+    }
+
+    #[inline(always)]
+    fn decompress_buffer_size(&self) -> usize {
+        N
+    }
+
+    #[cfg(feature = "alloc")]
+    #[inline(always)]
+    fn compress<R: Read, W: Write>(
+        &self,
+        reader: R,
+        writer: W,
+    ) -> Result<W::Output, LzssError<R::Error, W::Error>> {
+        Self::compress_heap(reader, writer)
+    }
+
+    #[inline(always)]
+    fn compress_with_buffer<R: Read, W: Write>(
+        &self,
+        reader: R,
+        writer: W,
+        buffer: &mut [u8],
+    ) -> Result<W::Output, LzssError<R::Error, W::Error>> {
+        assert!(buffer.len() >= N2);
+        Self::compress_with_buffer(reader, writer, (&mut buffer[..N2]).try_into().unwrap())
+    }
+
+    #[cfg(feature = "alloc")]
+    #[inline(always)]
+    fn decompress<R: Read, W: Write>(
+        &self,
+        reader: R,
+        writer: W,
+    ) -> Result<W::Output, LzssError<R::Error, W::Error>> {
+        Self::decompress_heap(reader, writer)
+    }
+
+    #[inline(always)]
+    fn decompress_with_buffer<R: Read, W: Write>(
+        &self,
+        reader: R,
+        writer: W,
+        buffer: &mut [u8],
+    ) -> Result<W::Output, LzssError<R::Error, W::Error>> {
+        assert!(buffer.len() >= N);
+        Self::decompress_with_buffer(reader, writer, (&mut buffer[..N]).try_into().unwrap())
+    }
+}
+
+impl LzssCodec for LzssDyn {
+    #[inline(always)]
+    fn compress_buffer_size(&self) -> usize {
+        2 * self.n()
+    }
+
+    #[inline(always)]
+    fn decompress_buffer_size(&self) -> usize {
+        self.n()
+    }
+
+    #[cfg(feature = "alloc")]
+    #[inline(always)]
+    fn compress<R: Read, W: Write>(
+        &self,
+        reader: R,
+        writer: W,
+    ) -> Result<W::Output, LzssError<R::Error, W::Error>> {
+        LzssDyn::compress(self, reader, writer)
+    }
+
+    #[inline(always)]
+    fn compress_with_buffer<R: Read, W: Write>(
+        &self,
+        reader: R,
+        writer: W,
+        buffer: &mut [u8],
+    ) -> Result<W::Output, LzssError<R::Error, W::Error>> {
+        LzssDyn::compress_with_buffer(self, reader, writer, buffer)
+    }
+
+    #[cfg(feature = "alloc")]
+    #[inline(always)]
+    fn decompress<R: Read, W: Write>(
+        &self,
+        reader: R,
+        writer: W,
+    ) -> Result<W::Output, LzssError<R::Error, W::Error>> {
+        LzssDyn::decompress(self, reader, writer)
+    }
+
+    #[inline(always)]
+    fn decompress_with_buffer<R: Read, W: Write>(
+        &self,
+        reader: R,
+        writer: W,
+        buffer: &mut [u8],
+    ) -> Result<W::Output, LzssError<R::Error, W::Error>> {
+        LzssDyn::decompress_with_buffer(self, reader, writer, buffer)
+    }
+}
+
+impl<const C: u8> LzssCodec for LzssFlagByte<C> {
+    #[inline(always)]
+    fn compress_buffer_size(&self) -> usize {
+        Self::N2
+    }
+
+    #[inline(always)]
+    fn decompress_buffer_size(&self) -> usize {
+        Self::N
+    }
+
+    #[cfg(feature = "alloc")]
+    #[inline(always)]
+    fn compress<R: Read, W: Write>(
+        &self,
+        reader: R,
+        writer: W,
+    ) -> Result<W::Output, LzssError<R::Error, W::Error>> {
+        Self::compress_heap(reader, writer)
+    }
+
+    #[inline(always)]
+    fn compress_with_buffer<R: Read, W: Write>(
+        &self,
+        reader: R,
+        writer: W,
+        buffer: &mut [u8],
+    ) -> Result<W::Output, LzssError<R::Error, W::Error>> {
+        assert!(buffer.len() >= Self::N2);
+        Self::compress_with_buffer(
+            reader,
+            writer,
+            (&mut buffer[..Self::N2]).try_into().unwrap(),
+        )
+    }
+
+    #[cfg(feature = "alloc")]
+    #[inline(always)]
+    fn decompress<R: Read, W: Write>(
+        &self,
+        reader: R,
+        writer: W,
+    ) -> Result<W::Output, LzssError<R::Error, W::Error>> {
+        Self::decompress_heap(reader, writer)
+    }
+
+    #[inline(always)]
+    fn decompress_with_buffer<R: Read, W: Write>(
+        &self,
+        reader: R,
+        writer: W,
+        buffer: &mut [u8],
+    ) -> Result<W::Output, LzssError<R::Error, W::Error>> {
+        assert!(buffer.len() >= Self::N);
+        Self::decompress_with_buffer(reader, writer, (&mut buffer[..Self::N]).try_into().unwrap())
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use crate::codec::LzssCodec;
+    use crate::generic::Lzss;
+    use crate::slice::SliceReader;
+    use crate::vec::VecWriter;
+    use crate::void::ResultLzssErrorVoidExt;
+    #[cfg(not(feature = "std"))]
+    use alloc::vec::Vec;
+
+    type TestLZSS = Lzss<10, 4, 0x20, { 1 << 10 }, { 2 << 10 }>;
+
+    const TEST_DATA: &[u8; 27] = b"Sample   Data   11221233123";
+    const COMPRESSED_DATA: [u8; 26] = [
+        169, 216, 109, 183, 11, 101, 149, 246, 13, 18, 195, 116, 176, 191, 81, 152, 204, 102, 83,
+        32, 0, 19, 57, 152, 3, 16,
+    ];
+
+    fn roundtrip<L: LzssCodec>(lzss: &L) {
+        let mut buffer = vec![0; lzss.compress_buffer_size() + 1];
+        let compressed = lzss
+            .compress_with_buffer(
+                SliceReader::new(TEST_DATA),
+                VecWriter::with_capacity(COMPRESSED_DATA.len()),
+                &mut buffer,
+            )
+            .void_unwrap();
+        assert_eq!(compressed, COMPRESSED_DATA);
+        let decompressed = lzss
+            .decompress(
+                SliceReader::new(&compressed),
+                VecWriter::with_capacity(TEST_DATA.len()),
+            )
+            .void_unwrap();
+        assert_eq!(decompressed, TEST_DATA);
+        let mut buffer: Vec<u8> = vec![0; lzss.decompress_buffer_size()];
+        let decompressed = lzss
+            .decompress_with_buffer(
+                SliceReader::new(&compressed),
+                VecWriter::with_capacity(TEST_DATA.len()),
+                &mut buffer,
+            )
+            .void_unwrap();
+        assert_eq!(decompressed, TEST_DATA);
+    }
+
+    #[test]
+    fn test_generic() {
+        roundtrip(&TestLZSS::new());
+    }
+
+    #[test]
+    fn test_dynamic() {
+        roundtrip(&TestLZSS::as_dyn());
+    }
+}
@@ -0,0 +1,494 @@
+use crate::error::LzssError;
+use crate::flag_byte::{LzssFlagByte, N, N2};
+use crate::read_write::{Read, Write};
+use crate::slice::SliceReader;
+#[cfg(all(feature = "safe", feature = "alloc", not(feature = "std")))]
+use core::convert::TryInto;
+use core::fmt::Display;
+use void::{unreachable, Void};
+
+const SIGNATURE: u32 = 0x636f_6d70; // "comp"
+const COMPRESSION_TYPE: u32 = 0x6c7a_7373; // "lzss"
+const HEADER_SIZE: usize = 0x180;
+
+/// The flag-byte format with the Apple `complzss` header, as used by kernelcaches and firmware images.
+///
+/// The header is 384 bytes long, all values are big-endian:
+/// * the signature `comp`
+/// * the compression type `lzss`
+/// * the Adler-32 checksum of the uncompressed data
+/// * the length of the uncompressed data
+/// * the length of the compressed data
+/// * padding (zeros when compressing, ignored when decompressing)
+///
+/// It is followed by the compressed data in the format of [`LzssFlagByte<0x20>`](LzssFlagByte).
+///
+/// # Example
+/// ```rust
+/// # use lzss::{LzssComplzss, SliceReader, VecWriter};
+/// let input = b"Example Data";
+/// let compressed = LzssComplzss::compress_stack(input, VecWriter::with_capacity(400));
+/// let compressed = compressed.unwrap();
+/// let decompressed =
+///     LzssComplzss::decompress_stack(SliceReader::new(&compressed), VecWriter::with_capacity(20));
+/// assert_eq!(decompressed.unwrap(), input);
+/// ```
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct LzssComplzss(());
+
+impl LzssComplzss {
+    /// Compress the input into the output.
+    ///
+    /// Since the header contains the length of the compressed data, the input is compressed twice.
+    ///
+    /// The buffer, with [`LzssFlagByte::N2`] bytes, is allocated on the stack.
+    pub fn compress_stack<W: Write>(
+        input: &[u8],
+        mut writer: W,
+    ) -> Result<W::Output, LzssComplzssError<Void, W::Error>> {
+        let mut buffer = [0; N2];
+        Self::compress_internal(input, &mut writer, &mut buffer)?;
+        writer.finish().map_err(LzssComplzssError::WriteError)
+    }
+
+    /// Compress the input into the output.
+    ///
+    /// Since the header contains the length of the compressed data, the input is compressed twice.
+    ///
+    /// The buffer, with [`LzssFlagByte::N2`] bytes, is allocated on the heap.
+    #[cfg_attr(docsrs, doc(cfg(any(feature = "alloc", feature = "std"))))]
+    #[cfg(feature = "alloc")]
+    pub fn compress_heap<W: Write>(
+        input: &[u8],
+        mut writer: W,
+    ) -> Result<W::Output, LzssComplzssError<Void, W::Error>> {
+        let mut buffer = vec![0; N2];
+        #[cfg(not(feature = "safe"))]
+        let buffer = unsafe { &mut *(buffer.as_mut_ptr().cast::<[u8; N2]>()) };
+        #[cfg(feature = "safe")]
+        let buffer: &mut [u8; N2] = (&mut buffer[..]).try_into().unwrap();
+        Self::compress_internal(input, &mut writer, buffer)?;
+        writer.finish().map_err(LzssComplzssError::WriteError)
+    }
+
+    /// Compress the input into the output.
+    ///
+    /// Since the header contains the length of the compressed data, the input is compressed twice.
+    pub fn compress_with_buffer<W: Write>(
+        input: &[u8],
+        mut writer: W,
+        buffer: &mut [u8; N2],
+    ) -> Result<W::Output, LzssComplzssError<Void, W::Error>> {
+        Self::compress_internal(input, &mut writer, buffer)?;
+        writer.finish().map_err(LzssComplzssError::WriteError)
+    }
+
+    /// Decompress the input into the output.
+    ///
+    /// The header is checked, only the compressed data (as specified in the header) is read,
+    /// and the length and the checksum of the decompressed data is verified.
+    ///
+    /// The buffer, with [`LzssFlagByte::N`] bytes, is allocated on the stack.
+    pub fn decompress_stack<R: Read, W: Write>(
+        mut reader: R,
+        mut writer: W,
+    ) -> Result<W::Output, LzssComplzssError<R::Error, W::Error>> {
+        let mut buffer = [0; N];
+        Self::decompress_internal(&mut reader, &mut writer, &mut buffer)?;
+        writer.finish().map_err(LzssComplzssError::WriteError)
+    }
+
+    /// Decompress the input into the output.
+    ///
+    /// The header is checked, only the compressed data (as specified in the header) is read,
+    /// and the length and the checksum of the decompressed data is verified.
+    ///
+    /// The buffer, with [`LzssFlagByte::N`] bytes, is allocated on the heap.
+    #[cfg_attr(docsrs, doc(cfg(any(feature = "alloc", feature = "std"))))]
+    #[cfg(feature = "alloc")]
+    pub fn decompress_heap<R: Read, W: Write>(
+        mut reader: R,
+        mut writer: W,
+    ) -> Result<W::Output, LzssComplzssError<R::Error, W::Error>> {
+        let mut buffer = vec![0; N];
+        #[cfg(not(feature = "safe"))]
+        let buffer = unsafe { &mut *(buffer.as_mut_ptr().cast::<[u8; N]>()) };
+        #[cfg(feature = "safe")]
+        let buffer: &mut [u8; N] = (&mut buffer[..]).try_into().unwrap();
+        Self::decompress_internal(&mut reader, &mut writer, buffer)?;
+        writer.finish().map_err(LzssComplzssError::WriteError)
+    }
+
+    /// Decompress the input into the output.
+    ///
+    /// The header is checked, only the compressed data (as specified in the header) is read,
+    /// and the length and the checksum of the decompressed data is verified.
+    pub fn decompress_with_buffer<R: Read, W: Write>(
+        mut reader: R,
+        mut writer: W,
+        buffer: &mut [u8; N],
+    ) -> Result<W::Output, LzssComplzssError<R::Error, W::Error>> {
+        Self::decompress_internal(&mut reader, &mut writer, buffer)?;
+        writer.finish().map_err(LzssComplzssError::WriteError)
+    }
+
+    fn compress_internal<W: Write>(
+        input: &[u8],
+        writer: &mut W,
+        buffer: &mut [u8; N2],
+    ) -> Result<(), LzssComplzssError<Void, W::Error>> {
+        let uncompressed_len =
+            u32::try_from(input.len()).map_err(|_| LzssComplzssError::TooLarge)?;
+
+        // first pass: only count the compressed bytes
+        let mut counter = CountWriter(0);
+        buffer.fill(0x20);
+        LzssFlagByte::<0x20>::compress_internal(&mut SliceReader::new(input), &mut counter, buffer)
+            .map_err(|e| match e {
+                LzssError::ReadError(e) | LzssError::WriteError(e) => unreachable(e),
+            })?;
+        let compressed_len = u32::try_from(counter.0).map_err(|_| LzssComplzssError::TooLarge)?;
+
+        let mut adler32 = Adler32::new();
+        for &b in input {
+            adler32.update(b);
+        }
+
+        let header = [
+            SIGNATURE,
+            COMPRESSION_TYPE,
+            adler32.finish(),
+            uncompressed_len,
+            compressed_len,
+        ];
+        for value in header {
+            for b in value.to_be_bytes() {
+                writer.write(b).map_err(LzssComplzssError::WriteError)?;
+            }
+        }
+        for _ in header.len() * 4..HEADER_SIZE {
+            writer.write(0).map_err(LzssComplzssError::WriteError)?;
+        }
+
+        // second pass: the real output
+        buffer.fill(0x20);
+        LzssFlagByte::<0x20>::compress_internal(&mut SliceReader::new(input), writer, buffer)
+            .map_err(|e| match e {
+                LzssError::ReadError(e) => unreachable(e),
+                LzssError::WriteError(e) => LzssComplzssError::WriteError(e),
+            })
+    }
+
+    fn decompress_internal<R: Read, W: Write>(
+        reader: &mut R,
+        writer: &mut W,
+        buffer: &mut [u8; N],
+    ) -> Result<(), LzssComplzssError<R::Error, W::Error>> {
+        let mut header = [0u32; 5];
+        for value in &mut header {
+            for _ in 0..4 {
+                match reader.read().map_err(LzssComplzssError::ReadError)? {
+                    None => return Err(LzssComplzssError::Truncated),
+                    Some(b) => *value = (*value << 8) | u32::from(b),
+                }
+            }
+        }
+        for _ in header.len() * 4..HEADER_SIZE {
+            if reader
+                .read()
+                .map_err(LzssComplzssError::ReadError)?
+                .is_none()
+            {
+                return Err(LzssComplzssError::Truncated);
+            }
+        }
+        let [signature, compression_type, checksum, uncompressed_len, compressed_len] = header;
+        if signature != SIGNATURE || compression_type != COMPRESSION_TYPE {
+            return Err(LzssComplzssError::InvalidHeader);
+        }
+
+        let mut limited_reader = LimitReader {
+            reader,
+            remaining: compressed_len,
+        };
+        let mut checksum_writer = ChecksumWriter {
+            writer,
+            adler32: Adler32::new(),
+            len: 0,
+        };
+        buffer.fill(0x20);
+        LzssFlagByte::<0x20>::decompress_internal(
+            &mut limited_reader,
+            &mut checksum_writer,
+            buffer,
+        )
+        .map_err(|e| match e {
+            LzssError::ReadError(e) => LzssComplzssError::ReadError(e),
+            LzssError::WriteError(e) => LzssComplzssError::WriteError(e),
+        })?;
+
+        if limited_reader.remaining != 0 {
+            return Err(LzssComplzssError::Truncated);
+        }
+        if checksum_writer.len != u64::from(uncompressed_len) {
+            return Err(LzssComplzssError::LengthMismatch {
+                expected: uncompressed_len,
+                actual: checksum_writer.len,
+            });
+        }
+        let actual = checksum_writer.adler32.finish();
+        if actual != checksum {
+            return Err(LzssComplzssError::ChecksumMismatch {
+                expected: checksum,
+                actual,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// The error returned by [`LzssComplzss`].
+#[derive(Debug, Eq, PartialEq)]
+pub enum LzssComplzssError<R, W> {
+    /// Contains the read error value.
+    ReadError(R),
+    /// Contains the write error value.
+    WriteError(W),
+    /// The header has not the signature `comp` and the compression type `lzss`.
+    InvalidHeader,
+    /// The input ended before the header or the compressed data was complete.
+    Truncated,
+    /// The length of the decompressed data does not match the header.
+    LengthMismatch {
+        /// The length from the header.
+        expected: u32,
+        /// The length of the decompressed data.
+        actual: u64,
+    },
+    /// The checksum of the decompressed data does not match the header.
+    ChecksumMismatch {
+        /// The checksum from the header.
+        expected: u32,
+        /// The checksum of the decompressed data.
+        actual: u32,
+    },
+    /// The data is larger than 4 GiB, the lengths don't fit into the header.
+    TooLarge,
+}
+
+impl<R: Display, W: Display> core::fmt::Display for LzssComplzssError<R, W> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            LzssComplzssError::ReadError(error) => write!(f, "Read error: {error}"),
+            LzssComplzssError::WriteError(error) => write!(f, "Write error: {error}"),
+            LzssComplzssError::InvalidHeader => f.write_str("Invalid header"),
+            LzssComplzssError::Truncated => f.write_str("Truncated input"),
+            LzssComplzssError::LengthMismatch { expected, actual } => {
+                write!(f, "Length mismatch, expected {expected} but got {actual}")
+            }
+            LzssComplzssError::ChecksumMismatch { expected, actual } => write!(
+                f,
+                "Checksum mismatch, expected 0x{expected:08x} but got 0x{actual:08x}"
+            ),
+            LzssComplzssError::TooLarge => f.write_str("Data too large"),
+        }
+    }
+}
+
+/// Implementation of [`Error`](std::error::Error) for [`LzssComplzssError`]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[cfg(feature = "std")]
+impl<R, W> std::error::Error for LzssComplzssError<R, W>
+where
+    R: std::error::Error + 'static,
+    W: std::error::Error + 'static,
+{
+    #[inline]
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LzssComplzssError::ReadError(error) => Some(error),
+            LzssComplzssError::WriteError(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+struct Adler32 {
+    a: u32,
+    b: u32,
+    n: u32,
+}
+
+impl Adler32 {
+    const MOD: u32 = 65521;
+    // The largest n such that 255n(n+1)/2 + (n+1)(MOD-1) <= 2^32-1
+    const NMAX: u32 = 5552;
+
+    #[inline(always)]
+    fn new() -> Self {
+        Adler32 { a: 1, b: 0, n: 0 }
+    }
+
+    #[inline(always)]
+    fn update(&mut self, data: u8) {
+        self.a += u32::from(data);
+        self.b += self.a;
+        self.n += 1;
+        if self.n == Self::NMAX {
+            self.a %= Self::MOD;
+            self.b %= Self::MOD;
+            self.n = 0;
+        }
+    }
+
+    #[inline(always)]
+    fn finish(&self) -> u32 {
+        ((self.b % Self::MOD) << 16) | (self.a % Self::MOD)
+    }
+}
+
+struct CountWriter(usize);
+
+impl Write for CountWriter {
+    type Output = ();
+    type Error = Void;
+
+    #[inline(always)]
+    fn write(&mut self, _data: u8) -> Result<(), Self::Error> {
+        self.0 += 1;
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn finish(self) -> Result<Self::Output, Self::Error> {
+        Ok(())
+    }
+}
+
+struct LimitReader<'a, R> {
+    reader: &'a mut R,
+    remaining: u32,
+}
+
+impl<R: Read> Read for LimitReader<'_, R> {
+    type Error = R::Error;
+
+    #[inline(always)]
+    fn read(&mut self) -> Result<Option<u8>, Self::Error> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        let result = self.reader.read()?;
+        if result.is_some() {
+            self.remaining -= 1;
+        }
+        Ok(result)
+    }
+}
+
+struct ChecksumWriter<'a, W> {
+    writer: &'a mut W,
+    adler32: Adler32,
+    len: u64,
+}
+
+impl<W: Write> Write for ChecksumWriter<'_, W> {
+    type Output = ();
+    type Error = W::Error;
+
+    #[inline(always)]
+    fn write(&mut self, data: u8) -> Result<(), Self::Error> {
+        self.writer.write(data)?;
+        self.adler32.update(data);
+        self.len += 1;
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn finish(self) -> Result<Self::Output, Self::Error> {
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use crate::complzss::{Adler32, LzssComplzss, LzssComplzssError};
+    use crate::slice::SliceReader;
+    use crate::vec::VecWriter;
+
+    #[test]
+    fn test_adler32() {
+        let mut adler32 = Adler32::new();
+        for &b in b"Wikipedia" {
+            adler32.update(b);
+        }
+        assert_eq!(adler32.finish(), 0x11e6_0398);
+        let mut adler32 = Adler32::new();
+        for _ in 0..100_000 {
+            adler32.update(0xff);
+        }
+        assert_eq!(adler32.finish(), 0x149a_302c);
+    }
+
+    #[test]
+    fn test_complzss() {
+        let big_test_data = include_bytes!("complzss.rs");
+        let compressed = LzssComplzss::compress_heap(
+            big_test_data,
+            VecWriter::with_capacity(big_test_data.len()),
+        )
+        .unwrap();
+        assert_eq!(&compressed[0..8], b"complzss");
+        assert_eq!(
+            &compressed[12..16],
+            (big_test_data.len() as u32).to_be_bytes()
+        );
+        assert_eq!(
+            &compressed[16..20],
+            ((compressed.len() - 0x180) as u32).to_be_bytes()
+        );
+        let decompressed = LzssComplzss::decompress_heap(
+            SliceReader::new(&compressed),
+            VecWriter::with_capacity(big_test_data.len()),
+        )
+        .unwrap();
+        assert_eq!(decompressed.as_slice(), big_test_data);
+
+        // trailing data is ignored
+        let mut trailing = compressed.clone();
+        trailing.extend_from_slice(b"trailing");
+        let decompressed = LzssComplzss::decompress_heap(
+            SliceReader::new(&trailing),
+            VecWriter::with_capacity(big_test_data.len()),
+        )
+        .unwrap();
+        assert_eq!(decompressed.as_slice(), big_test_data);
+
+        // errors
+        let mut broken = compressed.clone();
+        broken[0] = b'C';
+        assert_eq!(
+            LzssComplzss::decompress_heap(SliceReader::new(&broken), VecWriter::with_capacity(0)),
+            Err(LzssComplzssError::InvalidHeader)
+        );
+        assert_eq!(
+            LzssComplzss::decompress_heap(
+                SliceReader::new(&compressed[..compressed.len() - 1]),
+                VecWriter::with_capacity(0)
+            ),
+            Err(LzssComplzssError::Truncated)
+        );
+        let mut broken = compressed.clone();
+        broken[8] ^= 1;
+        assert!(matches!(
+            LzssComplzss::decompress_heap(SliceReader::new(&broken), VecWriter::with_capacity(0)),
+            Err(LzssComplzssError::ChecksumMismatch { .. })
+        ));
+        let mut broken = compressed;
+        broken[15] ^= 1;
+        assert!(matches!(
+            LzssComplzss::decompress_heap(SliceReader::new(&broken), VecWriter::with_capacity(0)),
+            Err(LzssComplzssError::LengthMismatch { .. })
+        ));
+    }
+}
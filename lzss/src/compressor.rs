@@ -0,0 +1,387 @@
+use crate::bits::BitWriter;
+use crate::checkpoint::{
+    read_header, read_into, read_u32, read_u8, write_header, write_u32, LzssCheckpointError,
+    KIND_COMPRESSOR,
+};
+use crate::dynamic::LzssDyn;
+use crate::macros::{get, set};
+use crate::options::LzssOptions;
+use crate::read_write::{Read, Write};
+use core::fmt::Display;
+
+/// A compressor which keeps its state between calls, for compressing a continuous stream.
+///
+/// The data is passed in pieces with [`LzssCompressor::write`] and [`LzssCompressor::flush`]
+/// makes all data so far decodable, without resetting the window. Thus the compression
+/// across the pieces is as good as compressing everything at once.
+///
+/// Without a flush the output is identical to [`LzssDyn::compress`].
+///
+/// The buffer must be at least `2 * (1 << EI)` bytes long (like for
+/// [`LzssDyn::compress_with_buffer`]), this is asserted at runtime.
+///
+/// # Example
+/// ```rust
+/// # use lzss::{LzssCompressor, LzssDyn, LzssDynError, LzssOptions, ResultLzssErrorVoidExt, SliceReader, VecWriter, Write};
+/// let my_lzss = LzssDyn::new(10, 4, 0x20)?.with_options(LzssOptions::END_MARKER);
+/// let mut buffer = [0; 2 << 10];
+/// let mut compressor = LzssCompressor::new(my_lzss, &mut buffer);
+/// let mut output = VecWriter::with_capacity(30);
+/// compressor.write(b"Example ", &mut output).unwrap();
+/// compressor.flush(&mut output).unwrap(); // everything so far can be decoded
+/// compressor.write(b"Data", &mut output).unwrap();
+/// compressor.finish(&mut output).unwrap();
+/// let output = output.finish().unwrap();
+/// let result = my_lzss.decompress(SliceReader::new(&output), VecWriter::with_capacity(30));
+/// assert_eq!(result.void_unwrap(), b"Example Data");
+/// # Ok::<(), LzssDynError>(())
+/// ```
+pub struct LzssCompressor<'a> {
+    lzss: LzssDyn,
+    buffer: &'a mut [u8],
+    buffer_end: usize,
+    r: usize,
+    s: usize,
+    start: usize,
+    bits: u32,
+    bits_in_buf: u8,
+    // The number of bytes compressed so far (at most N), for ADAPTIVE_OFFSET
+    seen: usize,
+}
+
+impl<'a> LzssCompressor<'a> {
+    /// Create a new compressor, the buffer must be at least `2 * (1 << EI)` bytes long.
+    pub fn new(lzss: LzssDyn, buffer: &'a mut [u8]) -> Self {
+        assert!(buffer.len() >= 2 * lzss.n());
+        buffer[..lzss.n() - lzss.f()].fill(lzss.c());
+        let r = lzss.n() - lzss.f();
+        LzssCompressor {
+            lzss,
+            buffer,
+            buffer_end: r,
+            r,
+            s: 0,
+            // With relative offsets the data in front of the input is not used
+            start: if lzss.options().is_relative() { r } else { 0 },
+            bits: 0,
+            bits_in_buf: 0,
+            seen: 0,
+        }
+    }
+
+    /// Compress the data into the writer.
+    ///
+    /// The data is only partially written, up to `(1 << EJ) + P` bytes and some bits
+    /// are kept, see [`LzssCompressor::flush`]. With [`LzssOptions::EXTENDED_LENGTH`] up to
+    /// `(1 << EI) + (1 << EJ) + P` bytes are kept, a match can be as long as the buffered data.
+    pub fn write<W: Write>(&mut self, data: &[u8], writer: &mut W) -> Result<(), W::Error> {
+        let mut bit_writer = BitWriter::with_bits(writer, self.bits, self.bits_in_buf);
+        for &b in data {
+            if self.buffer_end == 2 * self.lzss.n() {
+                self.compress_internal(&mut bit_writer, false)?;
+            }
+            let buffer = &mut *self.buffer;
+            set!(buffer, self.buffer_end, b);
+            self.buffer_end += 1;
+        }
+        self.compress_internal(&mut bit_writer, false)?;
+        (self.bits, self.bits_in_buf) = bit_writer.bits();
+        Ok(())
+    }
+
+    /// Write all data and a flush marker, the output is then byte-aligned.
+    ///
+    /// The decompression will output all data written so far and then skip the rest of the byte.
+    /// The window is kept, the compression continues with the next write.
+    ///
+    /// The flush marker is the reserved match of [`LzssOptions::END_MARKER`], which thus
+    /// has to be set, otherwise [`LzssFlushError::NoEndMarker`] is returned and nothing
+    /// is written.
+    pub fn flush<W: Write>(&mut self, writer: &mut W) -> Result<(), LzssFlushError<W::Error>> {
+        if !self.lzss.options().contains(LzssOptions::END_MARKER) {
+            return Err(LzssFlushError::NoEndMarker);
+        }
+        let mut bit_writer = BitWriter::with_bits(writer, self.bits, self.bits_in_buf);
+        self.compress_internal(&mut bit_writer, true)
+            .map_err(LzssFlushError::WriteError)?;
+        self.lzss
+            .write_marker(&mut bit_writer, self.r, 1, self.seen)
+            .map_err(LzssFlushError::WriteError)?;
+        bit_writer.flush().map_err(LzssFlushError::WriteError)?;
+        self.bits = 0;
+        self.bits_in_buf = 0;
+        Ok(())
+    }
+
+    /// Write all data (and the end marker, if [`LzssOptions::END_MARKER`] is set).
+    pub fn finish<W: Write>(mut self, writer: &mut W) -> Result<(), W::Error> {
+        let mut bit_writer = BitWriter::with_bits(writer, self.bits, self.bits_in_buf);
+        self.compress_internal(&mut bit_writer, true)?;
+        if self.lzss.options().contains(LzssOptions::END_MARKER) {
+            self.lzss
+                .write_marker(&mut bit_writer, self.r, 0, self.seen)?;
+        }
+        bit_writer.flush()
+    }
+
+    /// Save the state into the writer, it can be restored with [`LzssCompressor::restore`].
+    ///
+    /// The checkpoint contains the window (`(1 << EI) - (1 << EJ) - P` bytes), the not yet
+    /// compressed data (less than `(1 << EJ) + P` bytes, with [`LzssOptions::EXTENDED_LENGTH`]
+    /// up to `(1 << EI) + (1 << EJ) + P` bytes) and the not yet written bits,
+    /// plus 27 bytes for the parameters and positions.
+    ///
+    /// Since the not yet written bits are part of the checkpoint, the output written so far
+    /// has to be kept (or stored together with the checkpoint).
+    pub fn save<W: Write>(&self, mut writer: W) -> Result<W::Output, W::Error> {
+        write_header(&mut writer, KIND_COMPRESSOR, &self.lzss)?;
+        write_u32(&mut writer, self.r & (self.lzss.n() - 1))?;
+        write_u32(&mut writer, self.r - self.s.max(self.start))?;
+        write_u32(&mut writer, self.buffer_end - self.r)?;
+        write_u32(&mut writer, self.seen)?;
+        writer.write(self.bits_in_buf)?;
+        writer.write(self.bits as u8)?; // there are less than 8 bits in the buffer
+        for &b in &self.buffer[self.s..self.buffer_end] {
+            writer.write(b)?;
+        }
+        writer.finish()
+    }
+
+    /// Restore a compressor from a checkpoint created by [`LzssCompressor::save`].
+    ///
+    /// The parameters and the buffer size have to be the same as for the saved compressor,
+    /// the compression then continues exactly where it was saved.
+    pub fn restore<R: Read>(
+        lzss: LzssDyn,
+        buffer: &'a mut [u8],
+        mut reader: R,
+    ) -> Result<Self, LzssCheckpointError<R::Error>> {
+        assert!(buffer.len() >= 2 * lzss.n());
+        read_header(&mut reader, KIND_COMPRESSOR, &lzss)?;
+        let n = lzss.n();
+        let r = read_u32(&mut reader)?;
+        let valid = read_u32(&mut reader)?;
+        let pending = read_u32(&mut reader)?;
+        let seen = read_u32(&mut reader)?;
+        let bits_in_buf = read_u8(&mut reader)?;
+        let bits = read_u8(&mut reader)?;
+        if r >= n || valid > n - lzss.f() || seen > n || bits_in_buf >= 8 {
+            return Err(LzssCheckpointError::Invalid);
+        }
+        // Place the window at the same position (modulo N), this keeps the absolute positions
+        let r = if r >= n - lzss.f() { r } else { r + n };
+        let max_pending = if lzss.options().contains(LzssOptions::EXTENDED_LENGTH) {
+            2 * n - r
+        } else {
+            lzss.f() - 1
+        };
+        if pending > max_pending {
+            return Err(LzssCheckpointError::Invalid);
+        }
+        let s = r - (n - lzss.f());
+        read_into(&mut reader, &mut buffer[s..r + pending])?;
+        Ok(LzssCompressor {
+            lzss,
+            buffer,
+            buffer_end: r + pending,
+            r,
+            s,
+            start: r - valid,
+            bits: u32::from(bits),
+            bits_in_buf,
+            seen,
+        })
+    }
+
+    // Compress all data (`all`) or only while there is a complete look-ahead, this is the same
+    // as LzssDyn::compress_window_internal but without reading. With EXTENDED_LENGTH the
+    // look-ahead is the whole buffer (up to 2 * N), like in compress_window_internal.
+
+    fn compress_internal<W: Write>(
+        &mut self,
+        bit_writer: &mut BitWriter<'_, W>,
+        all: bool,
+    ) -> Result<(), W::Error> {
+        let lzss = self.lzss;
+        let n = lzss.n();
+        let extended = lzss.options().contains(LzssOptions::EXTENDED_LENGTH);
+        let buffer = &mut *self.buffer;
+        while self.r < self.buffer_end
+            && (all
+                || if extended {
+                    self.buffer_end == 2 * n
+                } else {
+                    self.buffer_end - self.r >= lzss.f()
+                })
+        {
+            let f1 = lzss.f().min(self.buffer_end - self.r);
+            let c = get!(buffer, self.r);
+            let (x, mut y) = LzssDyn::find_match(buffer, self.s.max(self.start), self.r, f1);
+            if extended && y == lzss.f() {
+                y = LzssDyn::extend_match(buffer, x, self.r, y, self.buffer_end);
+            }
+            if y <= lzss.p() {
+                bit_writer.write_bits(0x100 | u32::from(c), 9)?;
+                y = 1;
+            } else {
+                let position = if lzss.options().is_relative() {
+                    self.r - x - 1
+                } else {
+                    x & (n - 1)
+                };
+                lzss.write_match(bit_writer, position, y, self.seen)?;
+            }
+            self.r += y;
+            self.s += y;
+            self.seen = (self.seen + y).min(n);
+            if self.r >= n * 2 - lzss.f() {
+                buffer.copy_within(n..2 * n, 0);
+                self.buffer_end -= n;
+                self.r -= n;
+                self.s -= n;
+                self.start = self.start.saturating_sub(n);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The error returned by [`LzssCompressor::flush`].
+#[derive(Debug, Eq, PartialEq)]
+pub enum LzssFlushError<W> {
+    /// Contains the write error value.
+    WriteError(W),
+    /// The option [`LzssOptions::END_MARKER`] is not set, there is no flush marker.
+    NoEndMarker,
+}
+
+impl<W: Display> core::fmt::Display for LzssFlushError<W> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            LzssFlushError::WriteError(error) => write!(f, "Write error: {error}"),
+            LzssFlushError::NoEndMarker => f.write_str("Flush requires the END_MARKER option"),
+        }
+    }
+}
+
+/// Implementation of [`Error`](std::error::Error) for [`LzssFlushError`]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[cfg(feature = "std")]
+impl<W> std::error::Error for LzssFlushError<W>
+where
+    W: std::error::Error + 'static,
+{
+    #[inline]
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LzssFlushError::WriteError(error) => Some(error),
+            LzssFlushError::NoEndMarker => None,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use crate::compressor::{LzssCompressor, LzssFlushError};
+    use crate::dynamic::LzssDyn;
+    use crate::options::LzssOptions;
+    use crate::read_write::Write;
+    use crate::slice::SliceReader;
+    use crate::vec::VecWriter;
+    use crate::void::ResultLzssErrorVoidExt;
+    #[cfg(not(feature = "std"))]
+    use alloc::vec::Vec;
+    use void::ResultVoidExt;
+
+    #[test]
+    fn test_compressor() {
+        let mut big_test_data = include_bytes!("compressor.rs").to_vec();
+        // a long run for EXTENDED_LENGTH
+        big_test_data.splice(3000..3000, [0; 5000]);
+        for options in [
+            LzssOptions::NONE,
+            LzssOptions::RELATIVE,
+            LzssOptions::EXTENDED_LENGTH,
+            LzssOptions::EXTENDED_LENGTH | LzssOptions::RELATIVE | LzssOptions::END_MARKER,
+            LzssOptions::ADAPTIVE_OFFSET,
+            LzssOptions::ADAPTIVE_OFFSET | LzssOptions::EXTENDED_LENGTH | LzssOptions::END_MARKER,
+        ] {
+            let lzss = LzssDyn::new(10, 4, 0x20).unwrap().with_options(options);
+            let mut buffer = [0; 2 << 10];
+            let mut compressor = LzssCompressor::new(lzss, &mut buffer);
+            let mut output = VecWriter::with_capacity(big_test_data.len());
+            for chunk in big_test_data.chunks(77) {
+                compressor.write(chunk, &mut output).void_unwrap();
+            }
+            compressor.finish(&mut output).void_unwrap();
+            assert_eq!(
+                output.finish().void_unwrap(),
+                lzss.compress(
+                    SliceReader::new(&big_test_data),
+                    VecWriter::with_capacity(big_test_data.len()),
+                )
+                .void_unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_flush() {
+        let mut big_test_data = include_bytes!("compressor.rs").to_vec();
+        big_test_data.splice(3000..3000, [0; 5000]);
+        for options in [
+            LzssOptions::END_MARKER,
+            LzssOptions::END_MARKER | LzssOptions::RELATIVE,
+            LzssOptions::END_MARKER | LzssOptions::EXTENDED_LENGTH,
+            LzssOptions::END_MARKER | LzssOptions::ADAPTIVE_OFFSET,
+        ] {
+            let lzss = LzssDyn::new(10, 4, 0x20).unwrap().with_options(options);
+            let mut buffer = [0; 2 << 10];
+            let mut compressor = LzssCompressor::new(lzss, &mut buffer);
+            let mut output = Vec::new();
+            let mut written = 0;
+            for chunk in big_test_data.chunks(333) {
+                let mut packet = VecWriter::with_capacity(chunk.len());
+                compressor.write(chunk, &mut packet).void_unwrap();
+                compressor.flush(&mut packet).unwrap();
+                output.extend(packet.finish().void_unwrap());
+                written += chunk.len();
+                // everything so far is decodable
+                let partial = lzss
+                    .decompress(SliceReader::new(&output), VecWriter::with_capacity(written))
+                    .void_unwrap();
+                assert_eq!(partial.as_slice(), &big_test_data[..written]);
+            }
+            let mut packet = VecWriter::with_capacity(4);
+            compressor.finish(&mut packet).void_unwrap();
+            output.extend(packet.finish().void_unwrap());
+            let decompressed = lzss
+                .decompress(
+                    SliceReader::new(&output),
+                    VecWriter::with_capacity(big_test_data.len()),
+                )
+                .void_unwrap();
+            assert_eq!(decompressed.as_slice(), big_test_data);
+        }
+    }
+
+    #[test]
+    fn test_flush_without_end_marker() {
+        let lzss = LzssDyn::new(10, 4, 0x20).unwrap();
+        let mut buffer = [0; 2 << 10];
+        let mut compressor = LzssCompressor::new(lzss, &mut buffer);
+        let mut output = VecWriter::with_capacity(100);
+        compressor.write(b"Example Data", &mut output).void_unwrap();
+        assert_eq!(
+            compressor.flush(&mut output),
+            Err(LzssFlushError::NoEndMarker)
+        );
+        // nothing is lost, the compression continues
+        compressor.finish(&mut output).void_unwrap();
+        assert_eq!(
+            lzss.decompress_to_vec(SliceReader::new(&output.finish().void_unwrap()))
+                .void_unwrap(),
+            b"Example Data"
+        );
+    }
+}
@@ -0,0 +1,361 @@
+use crate::checkpoint::{
+    read_header, read_into, read_u32, read_u8, write_header, write_u32, LzssCheckpointError,
+    KIND_DECOMPRESSOR,
+};
+use crate::dynamic::LzssDyn;
+use crate::macros::{get, set};
+use crate::options::LzssOptions;
+use crate::read_write::{Read, Write};
+
+/// A decompressor which keeps its state between calls, for decompressing a continuous stream.
+///
+/// The compressed data is passed in pieces with [`LzssDecompressor::write`], each complete
+/// token is decompressed immediately. The output is identical to [`LzssDyn::decompress`].
+///
+/// The buffer must be at least `1 << EI` bytes long (like for
+/// [`LzssDyn::decompress_with_buffer`]), this is asserted at runtime.
+///
+/// # Example
+/// ```rust
+/// # use lzss::{LzssDecompressor, LzssDyn, LzssDynError, ResultLzssErrorVoidExt, SliceReader, VecWriter, Write};
+/// let my_lzss = LzssDyn::new(10, 4, 0x20)?;
+/// let compressed = my_lzss
+///     .compress(SliceReader::new(b"Example Data"), VecWriter::with_capacity(30))
+///     .void_unwrap();
+/// let mut buffer = [0; 1 << 10];
+/// let mut decompressor = LzssDecompressor::new(my_lzss, &mut buffer);
+/// let mut output = VecWriter::with_capacity(30);
+/// for chunk in compressed.chunks(3) {
+///     decompressor.write(chunk, &mut output).unwrap();
+/// }
+/// assert_eq!(output.finish().unwrap(), b"Example Data");
+/// # Ok::<(), LzssDynError>(())
+/// ```
+pub struct LzssDecompressor<'a> {
+    lzss: LzssDyn,
+    buffer: &'a mut [u8],
+    r: usize,
+    bits: u32,
+    bits_in_buf: u8,
+    finished: bool,
+    // The number of bytes decompressed so far (at most N), for ADAPTIVE_OFFSET
+    seen: usize,
+    // The position of the match which gets more bytes with EXTENDED_LENGTH
+    extend: Option<usize>,
+}
+
+impl<'a> LzssDecompressor<'a> {
+    /// Create a new decompressor, the buffer must be at least `1 << EI` bytes long.
+    pub fn new(lzss: LzssDyn, buffer: &'a mut [u8]) -> Self {
+        assert!(buffer.len() >= lzss.n());
+        buffer[..lzss.n()].fill(lzss.c());
+        LzssDecompressor {
+            lzss,
+            buffer,
+            r: lzss.n() - lzss.f(),
+            bits: 0,
+            bits_in_buf: 0,
+            finished: false,
+            seen: 0,
+            extend: None,
+        }
+    }
+
+    /// Decompress the data into the writer.
+    ///
+    /// Returns the number of used bytes, which is only less than the length of the data
+    /// when the end marker (see [`LzssOptions::END_MARKER`]) was found.
+    pub fn write<W: Write>(&mut self, data: &[u8], writer: &mut W) -> Result<usize, W::Error> {
+        for (i, &b) in data.iter().enumerate() {
+            if self.finished {
+                return Ok(i);
+            }
+            self.bits = (self.bits << 8) | u32::from(b);
+            self.bits_in_buf += 8;
+            self.decompress_internal(writer)?;
+        }
+        Ok(data.len())
+    }
+
+    /// Returns `true` when the end marker (see [`LzssOptions::END_MARKER`]) was found.
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Save the state into the writer, it can be restored with [`LzssDecompressor::restore`].
+    ///
+    /// The checkpoint contains the window (`1 << EI` bytes) and the not yet used bits
+    /// (a token is always decompressed completely), plus 27 bytes for the parameters and positions.
+    pub fn save<W: Write>(&self, mut writer: W) -> Result<W::Output, W::Error> {
+        write_header(&mut writer, KIND_DECOMPRESSOR, &self.lzss)?;
+        write_u32(&mut writer, self.r)?;
+        write_u32(&mut writer, self.bits as usize)?;
+        writer.write(self.bits_in_buf)?;
+        writer.write(u8::from(self.finished))?;
+        write_u32(&mut writer, self.seen)?;
+        // N marks that no match is extended
+        write_u32(&mut writer, self.extend.unwrap_or(self.lzss.n()))?;
+        for &b in &self.buffer[..self.lzss.n()] {
+            writer.write(b)?;
+        }
+        writer.finish()
+    }
+
+    /// Restore a decompressor from a checkpoint created by [`LzssDecompressor::save`].
+    ///
+    /// The parameters have to be the same as for the saved decompressor, the decompression
+    /// then continues exactly where it was saved.
+    pub fn restore<R: Read>(
+        lzss: LzssDyn,
+        buffer: &'a mut [u8],
+        mut reader: R,
+    ) -> Result<Self, LzssCheckpointError<R::Error>> {
+        assert!(buffer.len() >= lzss.n());
+        read_header(&mut reader, KIND_DECOMPRESSOR, &lzss)?;
+        let r = read_u32(&mut reader)?;
+        let bits = read_u32(&mut reader)? as u32;
+        let bits_in_buf = read_u8(&mut reader)?;
+        let finished = read_u8(&mut reader)?;
+        let seen = read_u32(&mut reader)?;
+        let extend = read_u32(&mut reader)?;
+        if r >= lzss.n()
+            || usize::from(bits_in_buf) > lzss.ei() + lzss.ej()
+            || finished > 1
+            || seen > lzss.n()
+            || extend > lzss.n()
+            || (extend < lzss.n() && !lzss.options().contains(LzssOptions::EXTENDED_LENGTH))
+        {
+            return Err(LzssCheckpointError::Invalid);
+        }
+        read_into(&mut reader, &mut buffer[..lzss.n()])?;
+        Ok(LzssDecompressor {
+            lzss,
+            buffer,
+            r,
+            bits,
+            bits_in_buf,
+            finished: finished == 1,
+            seen,
+            extend: if extend < lzss.n() {
+                Some(extend)
+            } else {
+                None
+            },
+        })
+    }
+
+    // Decompress all complete tokens, this is the same as LzssDyn::decompress_internal
+    // but the bits are pushed instead of pulled.
+
+    fn decompress_internal<W: Write>(&mut self, writer: &mut W) -> Result<(), W::Error> {
+        let lzss = self.lzss;
+        let n = lzss.n();
+        while !self.finished {
+            if let Some(i) = self.extend {
+                // The continuation bytes of a match with the maximal length
+                if self.bits_in_buf < 8 {
+                    break;
+                }
+                self.bits_in_buf -= 8;
+                let extra = (self.bits >> self.bits_in_buf) as u8;
+                let i = self.copy(writer, i, usize::from(extra))?;
+                self.extend = if extra == 255 { Some(i) } else { None };
+            } else if self.bits_in_buf == 0 {
+                break;
+            } else if (self.bits >> (self.bits_in_buf - 1)) & 1 != 0 {
+                if self.bits_in_buf < 9 {
+                    break;
+                }
+                self.bits_in_buf -= 9;
+                let c = (self.bits >> self.bits_in_buf) as u8;
+                writer.write(c)?;
+                let buffer = &mut *self.buffer;
+                set!(buffer, self.r, c);
+                self.r = (self.r + 1) & (n - 1);
+                self.seen = (self.seen + 1).min(n);
+            } else {
+                // With ADAPTIVE_OFFSET a match may be shorter than a literal
+                let offset_bits = lzss.offset_bits(self.seen);
+                let len = (1 + offset_bits + lzss.ej()) as u8;
+                if self.bits_in_buf < len {
+                    break;
+                }
+                self.bits_in_buf -= len;
+                let inp = self.bits >> self.bits_in_buf;
+                let position = ((inp >> lzss.ej()) as usize) & ((1 << offset_bits) - 1);
+                let j = (inp & ((1 << lzss.ej()) - 1)) as usize;
+                if lzss.is_marker(position, self.r, self.seen) {
+                    // The marker, the position of the next byte or the largest distance
+                    if j == 1 {
+                        // flush: the rest of the byte is padding
+                        self.bits_in_buf -= self.bits_in_buf % 8;
+                        continue;
+                    }
+                    // the end marker (length 0), other lengths end the stream as well
+                    self.finished = true;
+                    break;
+                }
+                let i = self.r.wrapping_sub(lzss.distance(position, self.r)) & (n - 1);
+                let i = self.copy(writer, i, j + lzss.p() + 1)?;
+                if lzss.options().contains(LzssOptions::EXTENDED_LENGTH)
+                    && j == (1 << lzss.ej()) - 1
+                {
+                    self.extend = Some(i);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Copy len bytes from the position i in the window, returns the position after them.
+    fn copy<W: Write>(
+        &mut self,
+        writer: &mut W,
+        mut i: usize,
+        len: usize,
+    ) -> Result<usize, W::Error> {
+        let n = self.lzss.n();
+        let buffer = &mut *self.buffer;
+        for _ in 0..len {
+            let b = get!(buffer, i);
+            writer.write(b)?;
+            set!(buffer, self.r, b);
+            self.r = (self.r + 1) & (n - 1);
+            i = (i + 1) & (n - 1);
+        }
+        self.seen = (self.seen + len).min(n);
+        Ok(i)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use crate::compressor::LzssCompressor;
+    use crate::decompressor::LzssDecompressor;
+    use crate::dynamic::LzssDyn;
+    use crate::options::LzssOptions;
+    use crate::read_write::Write;
+    use crate::slice::SliceReader;
+    use crate::vec::VecWriter;
+    use crate::void::ResultLzssErrorVoidExt;
+    #[cfg(not(feature = "std"))]
+    use alloc::vec::Vec;
+    use void::ResultVoidExt;
+
+    #[test]
+    fn test_decompressor() {
+        let mut big_test_data = include_bytes!("decompressor.rs").to_vec();
+        // a long run for EXTENDED_LENGTH
+        big_test_data.splice(3000..3000, [0; 5000]);
+        for options in [
+            LzssOptions::NONE,
+            LzssOptions::END_MARKER | LzssOptions::RELATIVE,
+            LzssOptions::END_MARKER | LzssOptions::EXTENDED_LENGTH,
+            LzssOptions::END_MARKER | LzssOptions::ADAPTIVE_OFFSET,
+            LzssOptions::END_MARKER
+                | LzssOptions::RELATIVE
+                | LzssOptions::EXTENDED_LENGTH
+                | LzssOptions::ADAPTIVE_OFFSET,
+        ] {
+            let lzss = LzssDyn::new(11, 4, 0x20).unwrap().with_options(options);
+            let mut compressed = lzss
+                .compress(
+                    SliceReader::new(&big_test_data),
+                    VecWriter::with_capacity(big_test_data.len()),
+                )
+                .void_unwrap();
+            if options.contains(LzssOptions::END_MARKER) {
+                compressed.extend_from_slice(b"rest");
+            }
+            let mut buffer = [0; 1 << 11];
+            let mut decompressor = LzssDecompressor::new(lzss, &mut buffer);
+            let mut output = VecWriter::with_capacity(big_test_data.len());
+            let mut used = 0;
+            for chunk in compressed.chunks(55) {
+                used += decompressor.write(chunk, &mut output).void_unwrap();
+            }
+            assert_eq!(output.finish().void_unwrap().as_slice(), big_test_data);
+            if options.contains(LzssOptions::END_MARKER) {
+                assert!(decompressor.is_finished());
+                assert_eq!(used, compressed.len() - 4);
+            }
+        }
+    }
+
+    #[test]
+    fn test_checkpoints() {
+        let mut big_test_data = include_bytes!("decompressor.rs").to_vec();
+        big_test_data.splice(3000..3000, [0; 5000]);
+        for options in [
+            LzssOptions::NONE,
+            LzssOptions::RELATIVE,
+            LzssOptions::EXTENDED_LENGTH | LzssOptions::RELATIVE,
+            LzssOptions::ADAPTIVE_OFFSET | LzssOptions::EXTENDED_LENGTH,
+        ] {
+            let lzss = LzssDyn::new(10, 4, 0x20).unwrap().with_options(options);
+            let expected = lzss
+                .compress(
+                    SliceReader::new(&big_test_data),
+                    VecWriter::with_capacity(big_test_data.len()),
+                )
+                .void_unwrap();
+
+            // compress, with a restart from a checkpoint after every chunk
+            let mut compressed = VecWriter::with_capacity(big_test_data.len());
+            let mut checkpoint = None::<Vec<u8>>;
+            for chunk in big_test_data.chunks(1000) {
+                let mut buffer = [0; 2 << 10];
+                let mut compressor = match &checkpoint {
+                    None => LzssCompressor::new(lzss, &mut buffer),
+                    Some(checkpoint) => {
+                        LzssCompressor::restore(lzss, &mut buffer, SliceReader::new(checkpoint))
+                            .unwrap()
+                    }
+                };
+                compressor.write(chunk, &mut compressed).void_unwrap();
+                checkpoint = Some(
+                    compressor
+                        .save(VecWriter::with_capacity(1 << 10))
+                        .void_unwrap(),
+                );
+            }
+            let mut buffer = [0; 2 << 10];
+            LzssCompressor::restore(lzss, &mut buffer, SliceReader::new(&checkpoint.unwrap()))
+                .unwrap()
+                .finish(&mut compressed)
+                .void_unwrap();
+            assert_eq!(compressed.finish().void_unwrap(), expected);
+
+            // decompress, with a restart from a checkpoint after every chunk
+            let mut output = VecWriter::with_capacity(big_test_data.len());
+            let mut checkpoint = None::<Vec<u8>>;
+            for chunk in expected.chunks(333) {
+                let mut buffer = [0; 1 << 10];
+                let mut decompressor = match &checkpoint {
+                    None => LzssDecompressor::new(lzss, &mut buffer),
+                    Some(checkpoint) => {
+                        LzssDecompressor::restore(lzss, &mut buffer, SliceReader::new(checkpoint))
+                            .unwrap()
+                    }
+                };
+                decompressor.write(chunk, &mut output).void_unwrap();
+                checkpoint = Some(
+                    decompressor
+                        .save(VecWriter::with_capacity(1 << 10))
+                        .void_unwrap(),
+                );
+            }
+            assert_eq!(output.finish().void_unwrap().as_slice(), big_test_data);
+
+            // the checkpoints are not interchangeable
+            let mut buffer = [0; 2 << 10];
+            assert!(LzssCompressor::restore(
+                lzss,
+                &mut buffer,
+                SliceReader::new(&checkpoint.unwrap())
+            )
+            .is_err());
+        }
+    }
+}
@@ -0,0 +1,275 @@
+use crate::read_write::{Read, Write};
+
+/// The kind of a delta filter, see [`DeltaReader`] and [`DeltaWriter`].
+///
+/// The filters replace each value with the difference to the previous one (wrapping), thus
+/// slowly changing data (like sensor logs or tables) becomes long runs of similar bytes.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum DeltaFilter {
+    /// The difference of each byte to the previous byte.
+    Byte,
+    /// The difference of each byte to the byte `N` bytes before, e.g. for records with
+    /// a size of `N` bytes or interleaved channels. `N` must be within `1..=256`.
+    Stride(usize),
+    /// The difference of each 16-bit little-endian value to the previous one.
+    U16Le,
+    /// The difference of each 32-bit little-endian value to the previous one.
+    U32Le,
+}
+
+impl DeltaFilter {
+    // The size of a value and the distance to the previous value, both in bytes.
+    #[inline(always)]
+    fn width_and_distance(self) -> (usize, usize) {
+        match self {
+            DeltaFilter::Byte => (1, 1),
+            DeltaFilter::Stride(distance) => {
+                assert!(
+                    (1..=256).contains(&distance),
+                    "the stride must be within 1..=256"
+                );
+                (1, distance)
+            }
+            DeltaFilter::U16Le => (2, 2),
+            DeltaFilter::U32Le => (4, 4),
+        }
+    }
+}
+
+// The common state of the reader and writer: the last `distance` bytes (of the original
+// data) and the carry within a multi-byte value.
+struct DeltaState {
+    encode: bool,
+    width: usize,
+    distance: usize,
+    position: usize,
+    carry: bool,
+    history: [u8; 256],
+}
+
+impl DeltaState {
+    #[inline(always)]
+    fn new(filter: DeltaFilter, encode: bool) -> Self {
+        let (width, distance) = filter.width_and_distance();
+        DeltaState {
+            encode,
+            width,
+            distance,
+            position: 0,
+            carry: false,
+            history: [0; 256],
+        }
+    }
+
+    #[inline(always)]
+    fn convert(&mut self, data: u8) -> u8 {
+        if self.position % self.width == 0 {
+            self.carry = false;
+        }
+        let previous = &mut self.history[self.position % self.distance];
+        let result = if self.encode {
+            let (result, borrow1) = data.overflowing_sub(*previous);
+            let (result, borrow2) = result.overflowing_sub(u8::from(self.carry));
+            self.carry = borrow1 || borrow2;
+            *previous = data;
+            result
+        } else {
+            let (result, carry1) = data.overflowing_add(*previous);
+            let (result, carry2) = result.overflowing_add(u8::from(self.carry));
+            self.carry = carry1 || carry2;
+            *previous = result;
+            result
+        };
+        self.position = (self.position + 1) % self.distance;
+        result
+    }
+}
+
+/// A reader which converts the data of the underlying reader with a [`DeltaFilter`].
+///
+/// Use [`DeltaReader::encoder`] in front of a compression and [`DeltaReader::decoder`] to read
+/// from something which emits the decompressed data (or use [`DeltaWriter`]).
+///
+/// # Example
+/// ```rust
+/// # use lzss::{DeltaFilter, DeltaReader, DeltaWriter, LzssDyn, LzssDynError, ResultLzssErrorVoidExt, SliceReader, VecWriter};
+/// let my_lzss = LzssDyn::new(10, 4, 0x20)?;
+/// // a slowly rising 16-bit value
+/// let input: Vec<u8> = (1000u16..1200).flat_map(u16::to_le_bytes).collect();
+/// let compressed = my_lzss
+///     .compress(
+///         DeltaReader::encoder(DeltaFilter::U16Le, SliceReader::new(&input)),
+///         VecWriter::with_capacity(400),
+///     )
+///     .void_unwrap();
+/// let unfiltered = my_lzss
+///     .compress(SliceReader::new(&input), VecWriter::with_capacity(400))
+///     .void_unwrap();
+/// assert!(compressed.len() * 4 < unfiltered.len());
+/// let output = my_lzss
+///     .decompress(
+///         SliceReader::new(&compressed),
+///         DeltaWriter::decoder(DeltaFilter::U16Le, VecWriter::with_capacity(400)),
+///     )
+///     .void_unwrap();
+/// assert_eq!(output, input);
+/// # Ok::<(), LzssDynError>(())
+/// ```
+pub struct DeltaReader<R> {
+    reader: R,
+    state: DeltaState,
+}
+
+impl<R: Read> DeltaReader<R> {
+    /// Create a reader which replaces the values with the differences.
+    ///
+    /// It will be asserted at runtime that a stride is within `1..=256`.
+    #[inline(always)]
+    #[must_use]
+    pub fn encoder(filter: DeltaFilter, reader: R) -> Self {
+        DeltaReader {
+            reader,
+            state: DeltaState::new(filter, true),
+        }
+    }
+
+    /// Create a reader which restores the values from the differences.
+    ///
+    /// It will be asserted at runtime that a stride is within `1..=256`.
+    #[inline(always)]
+    #[must_use]
+    pub fn decoder(filter: DeltaFilter, reader: R) -> Self {
+        DeltaReader {
+            reader,
+            state: DeltaState::new(filter, false),
+        }
+    }
+}
+
+impl<R: Read> Read for DeltaReader<R> {
+    /// The error of the underlying reader.
+    type Error = R::Error;
+    #[inline(always)]
+    fn read(&mut self) -> Result<Option<u8>, Self::Error> {
+        Ok(self.reader.read()?.map(|data| self.state.convert(data)))
+    }
+}
+
+/// A writer which converts the data with a [`DeltaFilter`] before writing it into the underlying writer.
+///
+/// Use [`DeltaWriter::decoder`] as the output of a decompression and [`DeltaWriter::encoder`] to
+/// convert data for something which receives the data (or use [`DeltaReader`]).
+pub struct DeltaWriter<W> {
+    writer: W,
+    state: DeltaState,
+}
+
+impl<W: Write> DeltaWriter<W> {
+    /// Create a writer which replaces the values with the differences.
+    ///
+    /// It will be asserted at runtime that a stride is within `1..=256`.
+    #[inline(always)]
+    #[must_use]
+    pub fn encoder(filter: DeltaFilter, writer: W) -> Self {
+        DeltaWriter {
+            writer,
+            state: DeltaState::new(filter, true),
+        }
+    }
+
+    /// Create a writer which restores the values from the differences.
+    ///
+    /// It will be asserted at runtime that a stride is within `1..=256`.
+    #[inline(always)]
+    #[must_use]
+    pub fn decoder(filter: DeltaFilter, writer: W) -> Self {
+        DeltaWriter {
+            writer,
+            state: DeltaState::new(filter, false),
+        }
+    }
+}
+
+impl<W: Write> Write for DeltaWriter<W> {
+    /// The output of the underlying writer.
+    type Output = W::Output;
+    /// The error of the underlying writer.
+    type Error = W::Error;
+    #[inline(always)]
+    fn write(&mut self, data: u8) -> Result<(), Self::Error> {
+        let data = self.state.convert(data);
+        self.writer.write(data)
+    }
+    #[inline(always)]
+    fn finish(self) -> Result<Self::Output, Self::Error> {
+        self.writer.finish()
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use crate::delta_filter::{DeltaFilter, DeltaReader, DeltaWriter};
+    use crate::read_write::{Read, Write};
+    use crate::slice::SliceReader;
+    use crate::vec::VecWriter;
+    #[cfg(not(feature = "std"))]
+    use alloc::vec::Vec;
+    use void::ResultVoidExt;
+
+    fn encode(filter: DeltaFilter, data: &[u8]) -> Vec<u8> {
+        let mut reader = DeltaReader::encoder(filter, SliceReader::new(data));
+        let mut result = Vec::new();
+        while let Some(data) = reader.read().void_unwrap() {
+            result.push(data);
+        }
+        result
+    }
+
+    fn decode(filter: DeltaFilter, data: &[u8]) -> Vec<u8> {
+        let mut writer = DeltaWriter::decoder(filter, VecWriter::with_capacity(data.len()));
+        for &b in data {
+            writer.write(b).void_unwrap();
+        }
+        writer.finish().void_unwrap()
+    }
+
+    #[test]
+    fn test_delta_filter() {
+        assert_eq!(encode(DeltaFilter::Byte, &[1, 3, 2, 2]), [1, 2, 0xff, 0]);
+        assert_eq!(
+            encode(DeltaFilter::Stride(3), &[1, 2, 3, 2, 4, 6, 3]),
+            [1, 2, 3, 1, 2, 3, 1]
+        );
+        // 0x00ff, 0x0100, 0x0080 and a truncated value
+        assert_eq!(
+            encode(
+                DeltaFilter::U16Le,
+                &[0xff, 0x00, 0x00, 0x01, 0x80, 0x00, 0x01]
+            ),
+            [0xff, 0x00, 0x01, 0x00, 0x80, 0xff, 0x81]
+        );
+        // 0x0001_0000, 0x0000_ffff
+        assert_eq!(
+            encode(DeltaFilter::U32Le, &[0, 0, 1, 0, 0xff, 0xff, 0, 0]),
+            [0, 0, 1, 0, 0xff, 0xff, 0xff, 0xff]
+        );
+
+        let input = include_bytes!("delta_filter.rs");
+        for filter in [
+            DeltaFilter::Byte,
+            DeltaFilter::Stride(1),
+            DeltaFilter::Stride(7),
+            DeltaFilter::Stride(256),
+            DeltaFilter::U16Le,
+            DeltaFilter::U32Le,
+        ] {
+            assert_eq!(decode(filter, &encode(filter, input)), input);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "the stride must be within 1..=256")]
+    fn test_invalid_stride() {
+        let _ = DeltaReader::encoder(DeltaFilter::Stride(0), SliceReader::new(&[]));
+    }
+}
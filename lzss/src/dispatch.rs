@@ -0,0 +1,121 @@
+/// Dispatch runtime parameters to the generic code.
+///
+/// The parameters of the [`LzssDyn`](crate::LzssDyn) are matched against a list of
+/// `(ei, ej, c)` or `(ei, ej, c, p)` parameter sets. When one fits, the first expression is evaluated with
+/// the given name bound to the matching [`Lzss`](crate::Lzss) type. Otherwise the second
+/// expression is evaluated with the given name bound to the [`LzssDyn`](crate::LzssDyn).
+///
+/// This combines the speed of the generic code for the common parameter sets with the
+/// flexibility of the dynamic code, at the cost of one generated function per listed set.
+///
+/// ```rust
+/// # use lzss::{lzss_dispatch, LzssDyn, LzssDynError, ResultLzssErrorVoidExt, SliceReader, VecWriter};
+/// let my_lzss: LzssDyn = "10,4,0x20".parse()?; // e.g. from a header
+/// let input = b"Example Data";
+/// let result = lzss_dispatch!(
+///     my_lzss,
+///     [(10, 4, 0x20), (12, 4, 0x00, 2)],
+///     MyLzss => MyLzss::compress_stack(SliceReader::new(input), VecWriter::with_capacity(30)),
+///     my_dyn => my_dyn.compress(SliceReader::new(input), VecWriter::with_capacity(30)),
+/// );
+/// assert_eq!(result.void_unwrap().len(), 14); // the output is 14 bytes long
+/// # Ok::<(), LzssDynError>(())
+/// ```
+#[macro_export]
+macro_rules! lzss_dispatch {
+    (
+        $lzss_dyn:expr,
+        [$(($ei:literal, $ej:literal, $c:literal $(, $p:literal)?)),* $(,)?],
+        $generic:ident => $generic_expr:expr,
+        $dynamic:ident => $dynamic_expr:expr $(,)?
+    ) => {{
+        let lzss_dyn: $crate::LzssDyn = $lzss_dyn;
+        $(
+            if lzss_dyn == <$crate::lzss_type!($ei, $ej, $c $(, $p)?)>::as_dyn() {
+                #[allow(dead_code)]
+                type $generic = $crate::lzss_type!($ei, $ej, $c $(, $p)?);
+                $generic_expr
+            } else
+        )*
+        {
+            let $dynamic = lzss_dyn;
+            $dynamic_expr
+        }
+    }};
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use crate::dynamic::LzssDyn;
+    use crate::slice::SliceReader;
+    use crate::vec::VecWriter;
+    use crate::void::ResultLzssErrorVoidExt;
+    #[cfg(not(feature = "std"))]
+    use alloc::vec::Vec;
+
+    const TEST_DATA: &[u8; 27] = b"Sample   Data   11221233123";
+
+    fn compress(lzss_dyn: LzssDyn) -> (bool, Vec<u8>) {
+        lzss_dispatch!(
+            lzss_dyn,
+            [(10, 4, 0x20), (10, 4, 0x00, 2)],
+            TestLzss => (
+                true,
+                TestLzss::compress_stack(
+                    SliceReader::new(TEST_DATA),
+                    VecWriter::with_capacity(TEST_DATA.len()),
+                )
+                .void_unwrap()
+            ),
+            test_lzss => (
+                false,
+                test_lzss
+                    .compress(
+                        SliceReader::new(TEST_DATA),
+                        VecWriter::with_capacity(TEST_DATA.len()),
+                    )
+                    .void_unwrap()
+            ),
+        )
+    }
+
+    #[test]
+    fn test_dispatch() {
+        let generic = LzssDyn::new(10, 4, 0x20).unwrap();
+        let dynamic = LzssDyn::new(10, 4, 0x00).unwrap();
+        let generic_p = LzssDyn::new_with_p(10, 4, 0x00, 2).unwrap();
+        let (is_generic, output) = compress(generic);
+        assert!(is_generic);
+        assert_eq!(
+            output,
+            generic
+                .compress(
+                    SliceReader::new(TEST_DATA),
+                    VecWriter::with_capacity(TEST_DATA.len()),
+                )
+                .void_unwrap()
+        );
+        let (is_generic, output) = compress(generic_p);
+        assert!(is_generic);
+        assert_eq!(
+            output,
+            generic_p
+                .compress(
+                    SliceReader::new(TEST_DATA),
+                    VecWriter::with_capacity(TEST_DATA.len()),
+                )
+                .void_unwrap()
+        );
+        let (is_generic, output) = compress(dynamic);
+        assert!(!is_generic);
+        assert_eq!(
+            output,
+            dynamic
+                .compress(
+                    SliceReader::new(TEST_DATA),
+                    VecWriter::with_capacity(TEST_DATA.len()),
+                )
+                .void_unwrap()
+        );
+    }
+}
@@ -6,24 +6,43 @@ use crate::bits::BitWriter;
 use crate::dynamic::LzssDyn;
 use crate::error::LzssError;
 use crate::macros::{get, search_loop, set};
+use crate::options::LzssOptions;
 use crate::read_write::{Read, Write};
 
 impl LzssDyn {
-    // Allow many single char names, this is done to copy the original code as close as possible.
-    #![allow(clippy::many_single_char_names)]
     #[inline(always)]
     pub(crate) fn compress_internal<R: Read, W: Write>(
         &self,
         reader: &mut R,
         writer: &mut W,
         buffer: &mut [u8],
+        max_distance: usize,
+    ) -> Result<(), LzssError<R::Error, W::Error>> {
+        let mut bit_writer = BitWriter::new(writer);
+        self.compress_window_internal(reader, &mut bit_writer, buffer, 0, max_distance)?;
+        bit_writer.flush().map_err(LzssError::WriteError)
+    }
+
+    // The window (buffer[..N - F]) has to be filled by the caller, usually with C.
+    // The ring_offset is added to all positions, it's used for continuing a stream and is
+    // the number of bytes compressed before (which are in the window).
+    // Matches are searched at most max_distance (up to N) bytes back.
+
+    // Allow many single char names, this is done to copy the original code as close as possible.
+    #[allow(clippy::many_single_char_names)]
+    #[inline(always)]
+    pub(crate) fn compress_window_internal<R: Read, W: Write>(
+        &self,
+        reader: &mut R,
+        bit_writer: &mut BitWriter<'_, W>,
+        buffer: &mut [u8],
+        ring_offset: usize,
+        max_distance: usize,
     ) -> Result<(), LzssError<R::Error, W::Error>> {
         // It is already ensured that EI+EJ are "reasonable"
         // And for generic: 1<<EI == N and 2*N == N2
         // And for dynamic: the buffer has the correct size
 
-        let mut bit_writer = BitWriter::new(writer);
-
         let mut buffer_end = self.n() - self.f();
         while buffer_end < 2 * self.n() {
             match reader.read().map_err(LzssError::ReadError)? {
@@ -37,25 +56,21 @@ impl LzssDyn {
 
         let mut r = self.n() - self.f();
         let mut s = 0;
+        // With relative offsets only the data seen so far is used, not the fill in front of it
+        let mut start = if self.options().is_relative() {
+            r - ring_offset.min(r)
+        } else {
+            0
+        };
+        // The number of bytes compressed so far (at most N), for ADAPTIVE_OFFSET
+        let mut seen = ring_offset.min(self.n());
         while r < buffer_end {
             let f1 = self.f().min(buffer_end - r);
-            let mut x = 0;
-            let mut y = 1;
             let c = get!(buffer, r);
-            for (i, &ci) in search_loop!(s, r, buffer) {
-                if ci == c {
-                    let mut j = 1;
-                    while j < f1 {
-                        if get!(buffer, i + j) != get!(buffer, r + j) {
-                            break;
-                        }
-                        j += 1;
-                    }
-                    if j > y {
-                        x = i;
-                        y = j;
-                    }
-                }
+            let s1 = s.max(start).max(r.saturating_sub(max_distance));
+            let (x, mut y) = Self::find_match(buffer, s1, r, f1);
+            if self.options().contains(LzssOptions::EXTENDED_LENGTH) && y == self.f() {
+                y = Self::extend_match(buffer, x, r, y, buffer_end);
             }
             if y <= self.p() {
                 bit_writer
@@ -63,20 +78,23 @@ impl LzssDyn {
                     .map_err(LzssError::WriteError)?;
                 y = 1;
             } else {
-                bit_writer
-                    .write_bits(
-                        (((x & (self.n() - 1)) as u32) << self.ej) | ((y - (self.p() + 1)) as u32),
-                        1 + self.ei + self.ej,
-                    )
+                let position = if self.options().is_relative() {
+                    r - x - 1
+                } else {
+                    (x + ring_offset) & (self.n() - 1)
+                };
+                self.write_match(bit_writer, position, y, seen)
                     .map_err(LzssError::WriteError)?;
             }
             r += y;
             s += y;
+            seen = (seen + y).min(self.n());
             if r >= self.n() * 2 - self.f() {
                 buffer.copy_within(self.n()..2 * self.n(), 0);
                 buffer_end -= self.n();
                 r -= self.n();
                 s -= self.n();
+                start = start.saturating_sub(self.n());
                 while buffer_end < 2 * self.n() {
                     match reader.read().map_err(LzssError::ReadError)? {
                         None => break,
@@ -88,6 +106,105 @@ impl LzssDyn {
                 }
             }
         }
-        bit_writer.flush().map_err(LzssError::WriteError)
+        if self.options().contains(LzssOptions::END_MARKER) {
+            self.write_marker(bit_writer, r + ring_offset, 0, seen)
+                .map_err(LzssError::WriteError)?;
+        }
+        Ok(())
+    }
+
+    // Search the longest match for buffer[r..r + f1] in buffer[s..r], the closest one wins.
+    // Returns the position and the length, the length is 1 if nothing is found.
+    #[allow(clippy::many_single_char_names)]
+    #[inline(always)]
+    pub(crate) fn find_match(buffer: &[u8], s: usize, r: usize, f1: usize) -> (usize, usize) {
+        let mut x = 0;
+        let mut y = 1;
+        let c = get!(buffer, r);
+        for (i, &ci) in search_loop!(s, r, buffer) {
+            if ci == c {
+                let mut j = 1;
+                while j < f1 {
+                    if get!(buffer, i + j) != get!(buffer, r + j) {
+                        break;
+                    }
+                    j += 1;
+                }
+                if j > y {
+                    x = i;
+                    y = j;
+                }
+            }
+        }
+        (x, y)
+    }
+
+    // Extend a match for buffer[r..buffer_end] at buffer[x..], which is already y bytes long.
+    #[inline(always)]
+    pub(crate) fn extend_match(
+        buffer: &[u8],
+        x: usize,
+        r: usize,
+        mut y: usize,
+        buffer_end: usize,
+    ) -> usize {
+        while r + y < buffer_end && get!(buffer, x + y) == get!(buffer, r + y) {
+            y += 1;
+        }
+        y
+    }
+
+    // Write a match of y bytes (more than P), the position is already converted for RELATIVE.
+    #[inline(always)]
+    pub(crate) fn write_match<W: Write>(
+        &self,
+        bit_writer: &mut BitWriter<'_, W>,
+        position: usize,
+        y: usize,
+        seen: usize,
+    ) -> Result<(), W::Error> {
+        bit_writer.write_bits(
+            ((position as u32) << self.ej) | ((y.min(self.f()) - (self.p() + 1)) as u32),
+            1 + self.offset_bits(seen) + self.ej,
+        )?;
+        if self.options().contains(LzssOptions::EXTENDED_LENGTH) && y >= self.f() {
+            Self::write_extended_length(bit_writer, y - self.f())?;
+        }
+        Ok(())
+    }
+
+    // Write the end marker (length 0) or the flush marker (length 1): a match with the
+    // position r of the next byte, or the largest distance of the offset width with RELATIVE.
+    #[inline(always)]
+    pub(crate) fn write_marker<W: Write>(
+        &self,
+        bit_writer: &mut BitWriter<'_, W>,
+        r: usize,
+        length: u32,
+        seen: usize,
+    ) -> Result<(), W::Error> {
+        let offset_bits = self.offset_bits(seen);
+        let position = if self.options().is_relative() {
+            (1 << offset_bits) - 1
+        } else {
+            r & (self.n() - 1)
+        };
+        bit_writer.write_bits(
+            ((position as u32) << self.ej) | length,
+            1 + offset_bits + self.ej,
+        )
+    }
+
+    // Write the continuation bytes of a match with the maximal length.
+    #[inline(always)]
+    pub(crate) fn write_extended_length<W: Write>(
+        bit_writer: &mut BitWriter<'_, W>,
+        mut extra: usize,
+    ) -> Result<(), W::Error> {
+        while extra >= 255 {
+            bit_writer.write_bits(255, 8)?;
+            extra -= 255;
+        }
+        bit_writer.write_bits(extra as u32, 8)
     }
 }
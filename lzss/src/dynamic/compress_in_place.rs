@@ -0,0 +1,213 @@
+/* This file is based on the LZSS encoder-decoder  (c) Haruhiko Okumura */
+
+// Notice: generic/compress_in_place.rs is generated from this file, see build.rs.
+
+use crate::bits::{BitReader, BitWriter};
+use crate::dynamic::LzssDyn;
+use crate::error::LzssError;
+use crate::macros::{get, search_loop, set};
+use crate::options::LzssOptions;
+use crate::read_write::Write;
+use crate::slice::SliceReader;
+use void::{ResultVoidExt, Void};
+
+impl LzssDyn {
+    // Allow many single char names, this is done to copy the original code as close as possible.
+    #![allow(clippy::many_single_char_names)]
+    // The options are handled inline, to keep the output in the same place as the input.
+    #[allow(clippy::too_many_lines)]
+    #[inline(always)]
+    pub(crate) fn compress_in_place_internal(
+        &self,
+        io: &mut [u8],
+        offset: usize,
+    ) -> (usize, Option<usize>) {
+        // It is already ensured that EI+EJ are "reasonable"
+
+        if offset >= io.len() {
+            return (0, None);
+        }
+        if offset < self.min_offset() {
+            return (0, Some(offset));
+        }
+        io[offset - (self.n() - self.f())..offset].fill(self.c());
+        let mut out_buf = 0;
+        let mut out_len = 0;
+        let mut out_ptr = 0;
+        let mut s = offset - (self.n() - self.f());
+        let mut r = offset;
+        let offset2 = self.n() * (1 + (offset + self.f()) / self.n()) - (offset + self.f());
+        // With relative offsets the fill in front of the input is not used
+        let start = if self.options().is_relative() {
+            offset
+        } else {
+            0
+        };
+        // The position in the buffer of compress_internal, which limits EXTENDED_LENGTH
+        let mut buffer_r = self.n() - self.f();
+
+        // The output is written directly into io, it always stays in front of the window.
+        macro_rules! write_bits {
+            ($data:expr, $len:expr) => {{
+                let len = $len;
+                out_buf = (out_buf << len) | ($data);
+                out_len += len;
+                while out_len >= 8 {
+                    out_len -= 8;
+                    set!(io, out_ptr, (out_buf >> out_len) as u8);
+                    out_ptr += 1;
+                }
+            }};
+        }
+
+        while r < io.len() {
+            let f1 = self.f().min(io.len() - r);
+            let c = get!(io, r);
+            let mut x = 0;
+            let mut y = 1;
+            let s1 = s.max(start);
+            for (i, &ci) in search_loop!(s1, r, io) {
+                if ci == c {
+                    let mut j = 1;
+                    while j < f1 {
+                        if get!(io, i + j) != get!(io, r + j) {
+                            break;
+                        }
+                        j += 1;
+                    }
+                    if j > y {
+                        x = i;
+                        y = j;
+                    }
+                }
+            }
+            if self.options().contains(LzssOptions::EXTENDED_LENGTH) && y == self.f() {
+                let limit = io.len().min(r + 2 * self.n() - buffer_r);
+                while r + y < limit && get!(io, x + y) == get!(io, r + y) {
+                    y += 1;
+                }
+            }
+            if y <= self.p() {
+                write_bits!(0x100 | usize::from(c), 9);
+                y = 1;
+            } else {
+                let position = if self.options().is_relative() {
+                    r - x - 1
+                } else {
+                    (x + offset2) & (self.n() - 1)
+                };
+                write_bits!(
+                    (position << self.ej) | (y.min(self.f()) - (self.p() + 1)),
+                    1 + self.offset_bits(r - offset) + self.ej
+                );
+                if self.options().contains(LzssOptions::EXTENDED_LENGTH) && y >= self.f() {
+                    let mut extra = y - self.f();
+                    while extra >= 255 {
+                        write_bits!(255, 8);
+                        extra -= 255;
+                    }
+                    write_bits!(extra, 8);
+                }
+            }
+
+            r += y;
+            s += y;
+            buffer_r += y;
+            if buffer_r >= self.n() * 2 - self.f() {
+                buffer_r -= self.n();
+            }
+
+            if out_ptr + self.min_gap_size() > s {
+                if out_len > 0 {
+                    set!(io, out_ptr, (out_buf << (8 - out_len)) as u8);
+                    out_ptr += 1;
+                }
+                return (out_ptr, Some(r));
+            }
+        }
+        if self.options().contains(LzssOptions::END_MARKER) {
+            // The gap in front of the window is large enough for the marker
+            let offset_bits = self.offset_bits(r - offset);
+            let position = if self.options().is_relative() {
+                (1 << offset_bits) - 1
+            } else {
+                (r + offset2) & (self.n() - 1)
+            };
+            write_bits!(position << self.ej, 1 + offset_bits + self.ej);
+        }
+
+        if out_len > 0 {
+            set!(io, out_ptr, (out_buf << (8 - out_len)) as u8);
+            out_ptr += 1;
+        }
+        (out_ptr, None)
+    }
+
+    // The continuation works like compress_internal, but the window is taken from the
+    // data in front of new_offset (which is still intact, including the fill with C).
+    // The already compressed data is parsed to get the number of valid bits
+    // and bytes compressed so far, the latter is required for the position in the ring.
+    #[inline(always)]
+    pub(crate) fn compress_in_place_continue_internal<W: Write>(
+        &self,
+        io: &[u8],
+        size: usize,
+        new_offset: usize,
+        writer: &mut W,
+        buffer: &mut [u8],
+    ) -> Result<(), LzssError<Void, W::Error>> {
+        // It is already ensured that EI+EJ are "reasonable"
+        // And for generic: 1<<EI == N and 2*N == N2
+        // And for dynamic: the buffer has the correct size
+
+        let mut reader = SliceReader::new(&io[new_offset..]);
+
+        if size == 0 {
+            // nothing was compressed, not even the window was prepared
+            buffer[..self.n() - self.f()].fill(self.c());
+            return self.compress_internal(&mut reader, writer, buffer, self.n());
+        }
+
+        let mut bits = 0;
+        let mut compressed = 0;
+        let mut compressed_reader = SliceReader::new(&io[..size]);
+        let mut bit_reader = BitReader::new(&mut compressed_reader);
+        while let Some((literal, inp)) = self.read_token(&mut bit_reader, compressed).void_unwrap()
+        {
+            if literal {
+                bits += 9;
+                compressed += 1;
+                continue;
+            }
+            let j = (inp & ((1 << self.ej) - 1)) as usize;
+            let len = match self.read_length(&mut bit_reader, j).void_unwrap() {
+                None => break,
+                Some(len) => len,
+            };
+            bits += 1 + self.offset_bits(compressed) + self.ej;
+            if self.options().contains(LzssOptions::EXTENDED_LENGTH) && j == (1 << self.ej) - 1 {
+                // the continuation bytes of EXTENDED_LENGTH
+                bits += 8 * ((len - self.f()) / 255 + 1);
+            }
+            compressed += len;
+        }
+
+        buffer[..self.n() - self.f()]
+            .copy_from_slice(&io[new_offset - (self.n() - self.f())..new_offset]);
+
+        // the last byte is written again since it may be incomplete, a complete one
+        // is written as is (the bit writer must start with less than 8 bits)
+        let last_bits = bits - 8 * (size - 1);
+        if last_bits == 8 {
+            writer.write(io[size - 1]).map_err(LzssError::WriteError)?;
+        }
+        let last_bits = last_bits % 8;
+        let mut bit_writer = BitWriter::with_bits(
+            writer,
+            u32::from(io[size - 1]) >> (8 - last_bits),
+            last_bits as u8,
+        );
+        self.compress_window_internal(&mut reader, &mut bit_writer, buffer, compressed, self.n())?;
+        bit_writer.flush().map_err(LzssError::WriteError)
+    }
+}
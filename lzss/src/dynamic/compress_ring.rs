@@ -0,0 +1,135 @@
+/* This file is based on the LZSS encoder-decoder  (c) Haruhiko Okumura */
+
+// Notice: generic/compress_ring.rs is generated from this file, see build.rs.
+
+use crate::bits::BitWriter;
+use crate::dynamic::LzssDyn;
+use crate::error::LzssError;
+use crate::macros::{get, set};
+use crate::options::LzssOptions;
+use crate::read_write::{Read, Write};
+
+impl LzssDyn {
+    // The buffer is used as a ring of N bytes: N - F bytes of history followed by
+    // F bytes of look-ahead. All positions are masked, thus no data has to be moved.
+    // The output is identical to compress_internal.
+
+    // The window (buffer[..N - F]) has to be filled by the caller, usually with C.
+
+    // Allow many single char names, this is done to copy the original code as close as possible.
+    #[allow(clippy::many_single_char_names)]
+    #[inline(always)]
+    pub(crate) fn compress_ring_internal<R: Read, W: Write>(
+        &self,
+        reader: &mut R,
+        writer: &mut W,
+        buffer: &mut [u8],
+    ) -> Result<(), LzssError<R::Error, W::Error>> {
+        // It is already ensured that EI+EJ are "reasonable"
+        // And for generic: 1<<EI == N
+        // And for dynamic: the buffer has the correct size
+
+        let mut bit_writer = BitWriter::new(writer);
+
+        let mask = self.n() - 1;
+        let mut r = self.n() - self.f();
+        let mut look_ahead = 0;
+        while look_ahead < self.f() {
+            match reader.read().map_err(LzssError::ReadError)? {
+                None => break,
+                Some(data) => {
+                    set!(buffer, r + look_ahead, data);
+                    look_ahead += 1;
+                }
+            }
+        }
+
+        // The number of bytes compressed so far (at most N), for RELATIVE and ADAPTIVE_OFFSET
+        let mut seen = 0;
+        // The position in the buffer of compress_internal, which limits EXTENDED_LENGTH
+        let mut buffer_r = self.n() - self.f();
+        while look_ahead > 0 {
+            let f1 = self.f().min(look_ahead);
+            let mut x = 0;
+            let mut y = 1;
+            let c = get!(buffer, r);
+            // With relative offsets only the data seen so far is used, not the fill in front of it
+            let window = if self.options().is_relative() {
+                seen.min(self.n() - self.f())
+            } else {
+                self.n() - self.f()
+            };
+            for d in 1..=window {
+                let i = r.wrapping_sub(d) & mask;
+                if get!(buffer, i) == c {
+                    let mut j = 1;
+                    while j < f1 {
+                        if get!(buffer, (i + j) & mask) != get!(buffer, (r + j) & mask) {
+                            break;
+                        }
+                        j += 1;
+                    }
+                    if j > y {
+                        x = i;
+                        y = j;
+                    }
+                }
+            }
+            if self.options().contains(LzssOptions::EXTENDED_LENGTH) && y == self.f() {
+                // The look-ahead is extended while the match continues, this only overwrites
+                // data in front of the window
+                while y < 2 * self.n() - buffer_r {
+                    if y == look_ahead {
+                        match reader.read().map_err(LzssError::ReadError)? {
+                            None => break,
+                            Some(data) => {
+                                set!(buffer, (r + look_ahead) & mask, data);
+                                look_ahead += 1;
+                            }
+                        }
+                    }
+                    if get!(buffer, (x + y) & mask) != get!(buffer, (r + y) & mask) {
+                        break;
+                    }
+                    y += 1;
+                }
+            }
+            if y <= self.p() {
+                bit_writer
+                    .write_bits(0x100 | u32::from(c), 9)
+                    .map_err(LzssError::WriteError)?;
+                y = 1;
+            } else {
+                let position = if self.options().is_relative() {
+                    r.wrapping_sub(x + 1) & mask
+                } else {
+                    x
+                };
+                self.write_match(&mut bit_writer, position, y, seen)
+                    .map_err(LzssError::WriteError)?;
+            }
+            r = (r + y) & mask;
+            look_ahead -= y;
+            seen = (seen + y).min(self.n());
+            buffer_r += y;
+            if buffer_r >= self.n() * 2 - self.f() {
+                buffer_r -= self.n();
+            }
+            while look_ahead < self.f() {
+                match reader.read().map_err(LzssError::ReadError)? {
+                    None => break,
+                    Some(data) => {
+                        set!(buffer, (r + look_ahead) & mask, data);
+                        look_ahead += 1;
+                    }
+                }
+            }
+        }
+        if self.options().contains(LzssOptions::END_MARKER) {
+            self.write_marker(&mut bit_writer, r, 0, seen)
+                .map_err(LzssError::WriteError)?;
+        }
+
+        bit_writer.flush().map_err(LzssError::WriteError)
+    }
+}
@@ -0,0 +1,113 @@
+/* This file is based on the LZSS encoder-decoder  (c) Haruhiko Okumura */
+
+// Notice: generic/compress_slice.rs is generated from this file, see build.rs.
+
+use crate::bits::BitWriter;
+use crate::dynamic::LzssDyn;
+use crate::macros::get;
+use crate::options::LzssOptions;
+use crate::read_write::Write;
+
+impl LzssDyn {
+    // Instead of a buffer the input is searched directly. The positions are the same
+    // as in compress_internal (the input starts at N - F), the positions in front of
+    // the input are referring to the initial fill with C.
+    // The output is identical to compress_internal.
+
+    // Allow many single char names, this is done to copy the original code as close as possible.
+    #[allow(clippy::many_single_char_names)]
+    #[inline(always)]
+    pub(crate) fn compress_slice_internal<W: Write>(
+        &self,
+        input: &[u8],
+        writer: &mut W,
+    ) -> Result<(), W::Error> {
+        // It is already ensured that EI+EJ are "reasonable"
+
+        let mut bit_writer = BitWriter::new(writer);
+
+        let nf = self.n() - self.f();
+        let end = nf + input.len();
+        let mut r = nf;
+        // The position in the buffer of compress_internal, which limits EXTENDED_LENGTH
+        let mut buffer_r = nf;
+        while r < end {
+            let f1 = self.f().min(end - r);
+            let look_ahead = &input[r - nf..r - nf + f1];
+            let mut x = 0;
+            let mut y = 1;
+            let c = get!(look_ahead, 0);
+            let s = r - nf;
+            // matches within the input
+            for i in (s.max(nf)..r).rev() {
+                if get!(input, i - nf) == c {
+                    let j = 1 + look_ahead[1..]
+                        .iter()
+                        .zip(&input[i - nf + 1..])
+                        .take_while(|(a, b)| a == b)
+                        .count();
+                    if j > y {
+                        x = i;
+                        y = j;
+                    }
+                }
+            }
+            // matches starting in the initial fill, which is not used with relative offsets
+            if c == self.c() && !self.options().is_relative() {
+                for i in (s..nf).rev() {
+                    let mut j = 1;
+                    while j < f1 {
+                        let ci = if i + j < nf {
+                            self.c()
+                        } else {
+                            get!(input, i + j - nf)
+                        };
+                        if ci != get!(look_ahead, j) {
+                            break;
+                        }
+                        j += 1;
+                    }
+                    if j > y {
+                        x = i;
+                        y = j;
+                    }
+                }
+            }
+            if self.options().contains(LzssOptions::EXTENDED_LENGTH) && y == self.f() {
+                let limit = end.min(r + 2 * self.n() - buffer_r);
+                while r + y < limit {
+                    let ci = if x + y < nf {
+                        self.c()
+                    } else {
+                        get!(input, x + y - nf)
+                    };
+                    if ci != get!(input, r + y - nf) {
+                        break;
+                    }
+                    y += 1;
+                }
+            }
+            if y <= self.p() {
+                bit_writer.write_bits(0x100 | u32::from(c), 9)?;
+                y = 1;
+            } else {
+                let position = if self.options().is_relative() {
+                    r - x - 1
+                } else {
+                    x & (self.n() - 1)
+                };
+                self.write_match(&mut bit_writer, position, y, r - nf)?;
+            }
+            r += y;
+            buffer_r += y;
+            if buffer_r >= self.n() * 2 - self.f() {
+                buffer_r -= self.n();
+            }
+        }
+        if self.options().contains(LzssOptions::END_MARKER) {
+            self.write_marker(&mut bit_writer, r, 0, r - nf)?;
+        }
+
+        bit_writer.flush()
+    }
+}
@@ -6,17 +6,31 @@ use crate::bits::BitReader;
 use crate::dynamic::LzssDyn;
 use crate::error::LzssError;
 use crate::macros::{get, set};
+use crate::options::LzssOptions;
 use crate::read_write::{Read, Write};
 
 impl LzssDyn {
-    // Allow many single char names, this is done to copy the original code as close as possible.
-    #[allow(clippy::many_single_char_names)]
     #[inline(always)]
     pub(crate) fn decompress_internal<R: Read, W: Write>(
         &self,
         reader: &mut R,
         writer: &mut W,
         buffer: &mut [u8],
+    ) -> Result<(), LzssError<R::Error, W::Error>> {
+        self.decompress_window_internal(reader, writer, &mut buffer[..self.n()])
+    }
+
+    // The window is used as ring buffer, its length has to be a power of two (N or, with
+    // RELATIVE, at least the largest distance in the stream).
+
+    // Allow many single char names, this is done to copy the original code as close as possible.
+    #[allow(clippy::many_single_char_names)]
+    #[inline(always)]
+    pub(crate) fn decompress_window_internal<R: Read, W: Write>(
+        &self,
+        reader: &mut R,
+        writer: &mut W,
+        window: &mut [u8],
     ) -> Result<(), LzssError<R::Error, W::Error>> {
         // It is already ensured that EI+EJ are "reasonable"
         // And for generic: 1<<EI == N and 2*N == N2
@@ -24,32 +38,121 @@ impl LzssDyn {
 
         let mut bit_reader = BitReader::new(reader);
 
-        let mut r = self.n() - self.f();
+        let mask = window.len() - 1;
+        let mut r = (self.n() - self.f()) & mask;
+        // The number of bytes written so far (at most N), for ADAPTIVE_OFFSET
+        let mut seen = 0;
         loop {
-            if let Some(inp) = bit_reader.read_bits(9).map_err(LzssError::ReadError)? {
-                if (inp & 0x100) != 0 {
-                    writer.write(inp as u8).map_err(LzssError::WriteError)?;
-                    set!(buffer, r, inp as u8);
-                    r = (r + 1) & (self.n() - 1);
-                } else if let Some(inp2) = bit_reader
-                    .read_bits(self.ei + self.ej - 8)
-                    .map_err(LzssError::ReadError)?
-                {
-                    let inp = (inp << (self.ei + self.ej - 8)) | inp2;
-                    let i = (inp >> self.ej) as usize;
-                    let j = (inp & ((1 << self.ej) - 1)) as usize;
-                    for k in 0..=j + self.p() {
-                        let b = get!(buffer, (i + k) & (self.n() - 1));
-                        writer.write(b).map_err(LzssError::WriteError)?;
-                        set!(buffer, r, b);
-                        r = (r + 1) & (self.n() - 1);
-                    }
-                } else {
-                    return Ok(());
+            let token = self.read_token(&mut bit_reader, seen);
+            let (literal, inp) = match token.map_err(LzssError::ReadError)? {
+                None => return Ok(()),
+                Some(token) => token,
+            };
+            if literal {
+                writer.write(inp as u8).map_err(LzssError::WriteError)?;
+                set!(window, r, inp as u8);
+                r = (r + 1) & mask;
+                seen = (seen + 1).min(self.n());
+                continue;
+            }
+            let position = (inp >> self.ej) as usize;
+            let j = (inp & ((1 << self.ej) - 1)) as usize;
+            if self.is_marker(position, r, seen) {
+                if j == 1 {
+                    // flush: the rest of the byte is padding
+                    bit_reader.align();
+                    continue;
                 }
-            } else {
+                // the end marker (length 0), other lengths end the stream as well
                 return Ok(());
             }
+            let len = self.read_length(&mut bit_reader, j);
+            let len = match len.map_err(LzssError::ReadError)? {
+                None => return Ok(()),
+                Some(len) => len,
+            };
+            let i = r.wrapping_sub(self.distance(position, r)) & mask;
+            for k in 0..len {
+                let b = get!(window, (i + k) & mask);
+                writer.write(b).map_err(LzssError::WriteError)?;
+                set!(window, r, b);
+                r = (r + 1) & mask;
+            }
+            seen = (seen + len).min(self.n());
+        }
+    }
+
+    // Read the flag and the rest of a token, the flag is true for a literal (of 8 bits)
+    // and false for a match (position and length field).
+    #[inline(always)]
+    pub(crate) fn read_token<R: Read>(
+        &self,
+        bit_reader: &mut BitReader<'_, R>,
+        seen: usize,
+    ) -> Result<Option<(bool, u32)>, R::Error> {
+        if self.options().contains(LzssOptions::ADAPTIVE_OFFSET) {
+            // A match may be shorter than a literal, thus the flag is read first
+            Ok(match bit_reader.read_bits(1)? {
+                None => None,
+                Some(0) => bit_reader
+                    .read_bits(self.offset_bits(seen) + self.ej)?
+                    .map(|inp| (false, inp)),
+                Some(_) => bit_reader.read_bits(8)?.map(|inp| (true, inp)),
+            })
+        } else {
+            Ok(match bit_reader.read_bits(9)? {
+                None => None,
+                Some(inp) if (inp & 0x100) != 0 => Some((true, inp & 0xff)),
+                Some(inp) => bit_reader
+                    .read_bits(self.ei + self.ej - 8)?
+                    .map(|inp2| (false, (inp << (self.ei + self.ej - 8)) | inp2)),
+            })
+        }
+    }
+
+    // Returns true if the match is the end or flush marker (only with END_MARKER).
+    #[inline(always)]
+    pub(crate) fn is_marker(&self, position: usize, r: usize, seen: usize) -> bool {
+        self.options().contains(LzssOptions::END_MARKER)
+            && if self.options().is_relative() {
+                position == (1 << self.offset_bits(seen)) - 1
+            } else {
+                position == r
+            }
+    }
+
+    // The distance (1..=N) from the position of the next byte r back to the match.
+    #[inline(always)]
+    pub(crate) fn distance(&self, position: usize, r: usize) -> usize {
+        if self.options().is_relative() {
+            position + 1
+        } else {
+            (r.wrapping_sub(position + 1) & (self.n() - 1)) + 1
+        }
+    }
+
+    // The length of a match with the length field j, plus the continuation bytes
+    // with EXTENDED_LENGTH. Returns None if the input ends within the continuation bytes.
+    #[inline(always)]
+    pub(crate) fn read_length<R: Read>(
+        &self,
+        bit_reader: &mut BitReader<'_, R>,
+        j: usize,
+    ) -> Result<Option<usize>, R::Error> {
+        let mut len = j + self.p() + 1;
+        if self.options().contains(LzssOptions::EXTENDED_LENGTH) && j == (1 << self.ej) - 1 {
+            loop {
+                match bit_reader.read_bits(8)? {
+                    None => return Ok(None),
+                    Some(extra) => {
+                        len += extra as usize;
+                        if extra != 255 {
+                            break;
+                        }
+                    }
+                }
+            }
         }
+        Ok(Some(len))
     }
 }
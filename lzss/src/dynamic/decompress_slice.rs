@@ -0,0 +1,238 @@
+/* This file is based on the LZSS encoder-decoder  (c) Haruhiko Okumura */
+
+// Notice: generic/decompress_slice.rs is generated from this file, see build.rs.
+
+use crate::bits::BitReader;
+use crate::dynamic::LzssDyn;
+use crate::error::LzssError;
+use crate::macros::{get, set};
+use crate::options::LzssOptions;
+use crate::read_write::Read;
+use crate::slice::SliceWriteError;
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use alloc::vec::Vec;
+
+impl LzssDyn {
+    // Instead of a ring buffer the already written output is used, a position in the
+    // ring buffer is converted into the distance to the current position. Since the
+    // position and the current position both advance with each byte the distance
+    // is constant for a whole match. Distances before the start of the output are
+    // referring to the initial fill with C.
+
+    // Allow many single char names, this is done to copy the original code as close as possible.
+    #[allow(clippy::many_single_char_names)]
+    #[inline(always)]
+    pub(crate) fn decompress_to_slice_internal<R: Read>(
+        &self,
+        reader: &mut R,
+        output: &mut [u8],
+    ) -> Result<usize, LzssError<R::Error, SliceWriteError>> {
+        // It is already ensured that EI+EJ are "reasonable"
+
+        let mut bit_reader = BitReader::new(reader);
+
+        let mut r = self.n() - self.f();
+        let mut o = 0;
+        loop {
+            let token = self.read_token(&mut bit_reader, o);
+            let (literal, inp) = match token.map_err(LzssError::ReadError)? {
+                None => return Ok(o),
+                Some(token) => token,
+            };
+            if literal {
+                if o == output.len() {
+                    return Err(LzssError::WriteError(SliceWriteError));
+                }
+                set!(output, o, inp as u8);
+                o += 1;
+                r = (r + 1) & (self.n() - 1);
+                continue;
+            }
+            let position = (inp >> self.ej) as usize;
+            let j = (inp & ((1 << self.ej) - 1)) as usize;
+            if self.is_marker(position, r, o) {
+                if j == 1 {
+                    // flush: the rest of the byte is padding
+                    bit_reader.align();
+                    continue;
+                }
+                // the end marker (length 0), other lengths end the stream as well
+                return Ok(o);
+            }
+            let len = self.read_length(&mut bit_reader, j);
+            let len = match len.map_err(LzssError::ReadError)? {
+                None => return Ok(o),
+                Some(len) => len,
+            };
+            if output.len() - o < len {
+                return Err(LzssError::WriteError(SliceWriteError));
+            }
+            let d = self.distance(position, r);
+            for _ in 0..len {
+                let b = if o >= d {
+                    get!(output, o - d)
+                } else {
+                    self.c()
+                };
+                set!(output, o, b);
+                o += 1;
+            }
+            r = (r + len) & (self.n() - 1);
+        }
+    }
+
+    // Allow many single char names, this is done to copy the original code as close as possible.
+    #[allow(clippy::many_single_char_names)]
+    #[cfg(feature = "alloc")]
+    #[inline(always)]
+    pub(crate) fn decompress_to_vec_internal<R: Read>(
+        &self,
+        reader: &mut R,
+        output: &mut Vec<u8>,
+    ) -> Result<(), R::Error> {
+        // It is already ensured that EI+EJ are "reasonable"
+
+        let mut bit_reader = BitReader::new(reader);
+
+        let mut r = self.n() - self.f();
+        loop {
+            let (literal, inp) = match self.read_token(&mut bit_reader, output.len())? {
+                None => return Ok(()),
+                Some(token) => token,
+            };
+            if literal {
+                output.push(inp as u8);
+                r = (r + 1) & (self.n() - 1);
+                continue;
+            }
+            let position = (inp >> self.ej) as usize;
+            let j = (inp & ((1 << self.ej) - 1)) as usize;
+            if self.is_marker(position, r, output.len()) {
+                if j == 1 {
+                    // flush: the rest of the byte is padding
+                    bit_reader.align();
+                    continue;
+                }
+                // the end marker (length 0), other lengths end the stream as well
+                return Ok(());
+            }
+            let len = match self.read_length(&mut bit_reader, j)? {
+                None => return Ok(()),
+                Some(len) => len,
+            };
+            let d = self.distance(position, r);
+            output.reserve(len);
+            for _ in 0..len {
+                let o = output.len();
+                let b = if o >= d {
+                    get!(output, o - d)
+                } else {
+                    self.c()
+                };
+                output.push(b);
+            }
+            r = (r + len) & (self.n() - 1);
+        }
+    }
+
+    // Allow many single char names, this is done to copy the original code as close as possible.
+    #[allow(clippy::many_single_char_names)]
+    #[inline(always)]
+    pub(crate) fn decompress_in_place_internal(
+        &self,
+        io: &mut [u8],
+        compressed_offset: usize,
+        compressed_len: usize,
+    ) -> Result<usize, SliceWriteError> {
+        // It is already ensured that EI+EJ are "reasonable"
+
+        let end = compressed_offset
+            .checked_add(compressed_len)
+            .ok_or(SliceWriteError)?;
+        if end > io.len() {
+            return Err(SliceWriteError);
+        }
+
+        let mut r = self.n() - self.f();
+        let mut o = 0;
+
+        // The input is read directly from io, the output must always stay in front of it.
+        let mut in_ptr = compressed_offset;
+        let mut in_buf: u32 = 0;
+        let mut in_len = 0;
+        macro_rules! read_bits {
+            ($len:expr) => {{
+                let len = $len;
+                while in_len < len {
+                    if in_ptr == end {
+                        return Ok(o);
+                    }
+                    in_buf = (in_buf << 8) | u32::from(get!(io, in_ptr));
+                    in_ptr += 1;
+                    in_len += 8;
+                }
+                in_len -= len;
+                (in_buf >> in_len) & ((1 << len) - 1)
+            }};
+        }
+
+        loop {
+            let (literal, inp) = if self.options().contains(LzssOptions::ADAPTIVE_OFFSET) {
+                // A match may be shorter than a literal, thus the flag is read first
+                if read_bits!(1) != 0 {
+                    (true, read_bits!(8))
+                } else {
+                    (false, read_bits!(self.offset_bits(o) + self.ej))
+                }
+            } else {
+                let inp = read_bits!(9);
+                if (inp & 0x100) != 0 {
+                    (true, inp & 0xff)
+                } else {
+                    let inp2 = read_bits!(self.ei + self.ej - 8);
+                    (false, (inp << (self.ei + self.ej - 8)) | inp2)
+                }
+            };
+            if literal {
+                if o >= in_ptr {
+                    return Err(SliceWriteError);
+                }
+                set!(io, o, inp as u8);
+                o += 1;
+                r = (r + 1) & (self.n() - 1);
+                continue;
+            }
+            let position = (inp >> self.ej) as usize;
+            let j = (inp & ((1 << self.ej) - 1)) as usize;
+            if self.is_marker(position, r, o) {
+                if j == 1 {
+                    // flush: the rest of the byte is padding
+                    in_len -= in_len % 8;
+                    continue;
+                }
+                // the end marker (length 0), other lengths end the stream as well
+                return Ok(o);
+            }
+            let mut len = j + self.p() + 1;
+            if self.options().contains(LzssOptions::EXTENDED_LENGTH) && j == (1 << self.ej) - 1 {
+                loop {
+                    let extra = read_bits!(8) as usize;
+                    len += extra;
+                    if extra != 255 {
+                        break;
+                    }
+                }
+            }
+            if o + len > in_ptr {
+                return Err(SliceWriteError);
+            }
+            let d = self.distance(position, r);
+            for _ in 0..len {
+                let b = if o >= d { get!(io, o - d) } else { self.c() };
+                set!(io, o, b);
+                o += 1;
+            }
+            r = (r + len) & (self.n() - 1);
+        }
+    }
+}
@@ -0,0 +1,346 @@
+use crate::bits::{BitReader, BitWriter};
+use crate::dynamic::LzssDyn;
+use crate::error::LzssError;
+use crate::macros::{get, set};
+use crate::read_write::{Read, ReadAt, Write};
+use core::fmt::Display;
+use void::Void;
+
+// The delta format is a variant of the normal format:
+// * a literal is `1` and the 8-bit character
+// * a match is `0`, a kind bit, `ei` bits of position and `ej` bits of length
+//   * kind `0`: the position is the distance back in the output minus one
+//   * kind `1`: the position is in the reference, relative to the expected position minus
+//     `n / 2`; the expected position moves along with the output and is set to the end of each
+//     match in the reference, thus it follows insertions and deletions
+// There is no initial buffer, all data is either from the output or the reference.
+
+impl LzssDyn {
+    /// Compress the new data with the old data as reference.
+    ///
+    /// Besides the data already written, matches can refer into the old data (within `1 << EI`
+    /// bytes around the position which corresponds to the current position of the new data).
+    /// When the new data is mostly the old data with some changes, the output is very small.
+    ///
+    /// The output is a separate format, it can only be decompressed with
+    /// [`LzssDyn::decompress_delta`], using the same old data.
+    ///
+    /// It will be asserted at runtime that there are no options.
+    ///
+    /// ```rust
+    /// # use lzss::{LzssDyn, LzssDynError, ResultLzssErrorVoidExt, SliceReader, VecWriter};
+    /// let my_lzss = LzssDyn::new(12, 6, 0)?;
+    /// let old = b"The quick brown fox jumps over the lazy dog.".repeat(10);
+    /// let mut new = old.clone();
+    /// new[200] = b'X';
+    /// let compressed = my_lzss.compress_delta(&old, &new, VecWriter::with_capacity(100));
+    /// let compressed = compressed.void_unwrap();
+    /// assert!(compressed.len() < 40);
+    /// let decompressed = my_lzss.decompress_delta(
+    ///     SliceReader::new(&compressed),
+    ///     &old[..],
+    ///     VecWriter::with_capacity(new.len()),
+    /// );
+    /// assert_eq!(decompressed.unwrap(), new);
+    /// # Ok::<(), LzssDynError>(())
+    /// ```
+    pub fn compress_delta<W: Write>(
+        &self,
+        old: &[u8],
+        new: &[u8],
+        mut writer: W,
+    ) -> Result<W::Output, LzssError<Void, W::Error>> {
+        assert!(self.options.is_empty(), "options are not supported");
+        self.compress_delta_internal(old, new, &mut writer)
+            .map_err(LzssError::WriteError)?;
+        writer.finish().map_err(LzssError::WriteError)
+    }
+
+    /// Decompress the input data into the output, the old data is the reference.
+    ///
+    /// The buffer, with `1 << EI` bytes, is allocated on the heap.
+    #[cfg_attr(docsrs, doc(cfg(any(feature = "alloc", feature = "std"))))]
+    #[cfg(feature = "alloc")]
+    #[allow(clippy::type_complexity)]
+    pub fn decompress_delta<R: Read, A: ReadAt, W: Write>(
+        &self,
+        mut reader: R,
+        mut old: A,
+        mut writer: W,
+    ) -> Result<W::Output, LzssDeltaError<R::Error, A::Error, W::Error>> {
+        let mut buffer = vec![0; self.n()];
+        self.decompress_delta_internal(&mut reader, &mut old, &mut writer, &mut buffer)?;
+        Ok(writer.finish().map_err(LzssError::WriteError)?)
+    }
+
+    /// Decompress the input data into the output, the old data is the reference.
+    ///
+    /// It will be asserted at runtime that the buffer is at least `1 << EI`.
+    #[allow(clippy::type_complexity)]
+    pub fn decompress_delta_with_buffer<R: Read, A: ReadAt, W: Write>(
+        &self,
+        mut reader: R,
+        mut old: A,
+        mut writer: W,
+        buffer: &mut [u8],
+    ) -> Result<W::Output, LzssDeltaError<R::Error, A::Error, W::Error>> {
+        assert!(buffer.len() >= self.n());
+        self.decompress_delta_internal(&mut reader, &mut old, &mut writer, buffer)?;
+        Ok(writer.finish().map_err(LzssError::WriteError)?)
+    }
+
+    // Allow many single char names, the search uses the names of find_match (c, i, j, x, y).
+    #[allow(clippy::many_single_char_names)]
+    fn compress_delta_internal<W: Write>(
+        &self,
+        old: &[u8],
+        new: &[u8],
+        writer: &mut W,
+    ) -> Result<(), W::Error> {
+        assert!(self.options.is_empty(), "options are not supported");
+        let half = self.n() / 2;
+        let mut bit_writer = BitWriter::new(writer);
+        let mut r = 0;
+        let mut expected = 0usize;
+        while r < new.len() {
+            let look_ahead = &new[r..new.len().min(r + self.f())];
+            let c = look_ahead[0];
+            let mut kind = 0;
+            let mut x = 0;
+            let mut y = 1;
+            // search the output, the closest one wins
+            for i in (r.saturating_sub(self.n())..r).rev() {
+                if get!(new, i) == c {
+                    let j = 1 + look_ahead[1..]
+                        .iter()
+                        .zip(&new[i + 1..])
+                        .take_while(|(a, b)| a == b)
+                        .count();
+                    if j > y {
+                        x = r - i - 1;
+                        y = j;
+                        if y == look_ahead.len() {
+                            break;
+                        }
+                    }
+                }
+            }
+            // search the reference
+            if y < look_ahead.len() {
+                for i in expected.saturating_sub(half)..old.len().min(expected + half) {
+                    if get!(old, i) == c {
+                        let j = 1 + look_ahead[1..]
+                            .iter()
+                            .zip(&old[i + 1..])
+                            .take_while(|(a, b)| a == b)
+                            .count();
+                        if j > y {
+                            kind = 1;
+                            x = i;
+                            y = j;
+                            if y == look_ahead.len() {
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+            if y <= self.p() {
+                bit_writer.write_bits(0x100 | u32::from(c), 9)?;
+                y = 1;
+                expected += 1;
+            } else {
+                let position = if kind == 0 {
+                    expected += y;
+                    x
+                } else {
+                    let position = x + half - expected;
+                    expected = x + y;
+                    position
+                };
+                // the flag is written separately, since the token may be longer than 25 bits
+                bit_writer.write_bits(0, 1)?;
+                bit_writer.write_bits(
+                    (kind << (self.ei + self.ej))
+                        | ((position as u32) << self.ej)
+                        | ((y - (self.p() + 1)) as u32),
+                    1 + self.ei + self.ej,
+                )?;
+            }
+            r += y;
+        }
+        bit_writer.flush()
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn decompress_delta_internal<R: Read, A: ReadAt, W: Write>(
+        &self,
+        reader: &mut R,
+        old: &mut A,
+        writer: &mut W,
+        buffer: &mut [u8],
+    ) -> Result<(), LzssDeltaError<R::Error, A::Error, W::Error>> {
+        assert!(self.options.is_empty(), "options are not supported");
+        let half = self.n() / 2;
+        let mut bit_reader = BitReader::new(reader);
+        let mut r = 0;
+        let mut expected = 0;
+        loop {
+            let inp = match bit_reader.read_bits(9).map_err(LzssError::ReadError)? {
+                None => return Ok(()),
+                Some(inp) => inp,
+            };
+            if (inp & 0x100) != 0 {
+                writer.write(inp as u8).map_err(LzssError::WriteError)?;
+                set!(buffer, r & (self.n() - 1), inp as u8);
+                r += 1;
+                expected += 1;
+                continue;
+            }
+            let inp2 = match bit_reader
+                .read_bits(self.ei + self.ej - 7)
+                .map_err(LzssError::ReadError)?
+            {
+                None => return Ok(()),
+                Some(inp2) => inp2,
+            };
+            let inp = (inp << (self.ei + self.ej - 7)) | inp2;
+            let position = ((inp >> self.ej) as usize) & (self.n() - 1);
+            let len = (inp & ((1 << self.ej) - 1)) as usize + self.p() + 1;
+            if (inp >> (self.ei + self.ej)) & 1 == 0 {
+                if position >= r {
+                    return Err(LzssDeltaError::InvalidReference);
+                }
+                for _ in 0..len {
+                    let b = get!(buffer, (r - position - 1) & (self.n() - 1));
+                    writer.write(b).map_err(LzssError::WriteError)?;
+                    set!(buffer, r & (self.n() - 1), b);
+                    r += 1;
+                }
+                expected += len;
+            } else {
+                let i = match (expected + position).checked_sub(half) {
+                    None => return Err(LzssDeltaError::InvalidReference),
+                    Some(i) => i,
+                };
+                expected = i + len;
+                for k in 0..len {
+                    let b = old
+                        .read_at(i + k)
+                        .map_err(LzssDeltaError::ReferenceError)?
+                        .ok_or(LzssDeltaError::InvalidReference)?;
+                    writer.write(b).map_err(LzssError::WriteError)?;
+                    set!(buffer, r & (self.n() - 1), b);
+                    r += 1;
+                }
+            }
+        }
+    }
+}
+
+/// The error returned by [`LzssDyn::decompress_delta`].
+#[derive(Debug, Eq, PartialEq)]
+pub enum LzssDeltaError<R, A, W> {
+    /// Contains the read or write error.
+    Lzss(LzssError<R, W>),
+    /// Contains the read error value of the reference.
+    ReferenceError(A),
+    /// A match is referring to data before the start or beyond the end of the reference or output.
+    InvalidReference,
+}
+
+impl<R, A, W> From<LzssError<R, W>> for LzssDeltaError<R, A, W> {
+    #[inline]
+    fn from(error: LzssError<R, W>) -> Self {
+        LzssDeltaError::Lzss(error)
+    }
+}
+
+impl<R: Display, A: Display, W: Display> core::fmt::Display for LzssDeltaError<R, A, W> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            LzssDeltaError::Lzss(error) => error.fmt(f),
+            LzssDeltaError::ReferenceError(error) => write!(f, "Reference read error: {error}"),
+            LzssDeltaError::InvalidReference => f.write_str("Invalid reference"),
+        }
+    }
+}
+
+/// Implementation of [`Error`](std::error::Error) for [`LzssDeltaError`]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[cfg(feature = "std")]
+impl<R, A, W> std::error::Error for LzssDeltaError<R, A, W>
+where
+    R: std::error::Error + 'static,
+    A: std::error::Error + 'static,
+    W: std::error::Error + 'static,
+{
+    #[inline]
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LzssDeltaError::Lzss(error) => error.source(),
+            LzssDeltaError::ReferenceError(error) => Some(error),
+            LzssDeltaError::InvalidReference => None,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use crate::dynamic::{LzssDeltaError, LzssDyn};
+    use crate::slice::SliceReader;
+    use crate::vec::VecWriter;
+    use crate::void::ResultLzssErrorVoidExt;
+    #[cfg(not(feature = "std"))]
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_delta() {
+        let lzss = LzssDyn::new(12, 6, 0).unwrap();
+        let old = include_bytes!("delta.rs");
+        // some changes, insertions and deletions
+        let mut new = Vec::from(&old[..]);
+        new[100] = b'#';
+        new.splice(2000..2000, b"inserted data".iter().copied());
+        new.drain(5000..5100);
+        new.splice(7000..7000, old[6000..7000].iter().copied());
+        let compressed = lzss
+            .compress_delta(old, &new, VecWriter::with_capacity(new.len()))
+            .void_unwrap();
+        let plain = lzss
+            .compress(SliceReader::new(&new), VecWriter::with_capacity(new.len()))
+            .void_unwrap();
+        assert!(compressed.len() * 5 < plain.len());
+        let decompressed = lzss
+            .decompress_delta(
+                SliceReader::new(&compressed),
+                &old[..],
+                VecWriter::with_capacity(new.len()),
+            )
+            .unwrap();
+        assert_eq!(decompressed, new);
+        // without the old data the reference is invalid
+        assert_eq!(
+            lzss.decompress_delta(
+                SliceReader::new(&compressed),
+                &old[..50],
+                VecWriter::with_capacity(new.len()),
+            ),
+            Err(LzssDeltaError::InvalidReference)
+        );
+        // an empty reference is the same as a normal compression (in the delta format)
+        let compressed = lzss
+            .compress_delta(&[], &new, VecWriter::with_capacity(new.len()))
+            .void_unwrap();
+        let mut buffer = [0; 1 << 12];
+        let decompressed = lzss
+            .decompress_delta_with_buffer(
+                SliceReader::new(&compressed),
+                &[][..],
+                VecWriter::with_capacity(new.len()),
+                &mut buffer,
+            )
+            .unwrap();
+        assert_eq!(decompressed, new);
+    }
+}
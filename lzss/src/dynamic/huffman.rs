@@ -0,0 +1,608 @@
+use crate::bits::BitReader;
+#[cfg(feature = "alloc")]
+use crate::bits::BitWriter;
+use crate::dynamic::LzssDyn;
+use crate::error::LzssError;
+use crate::macros::{get, set};
+use crate::read_write::{Read, Write};
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use alloc::vec::Vec;
+use core::fmt::Display;
+
+// The huffman format uses the tokens of the normal format, but they are entropy coded in blocks.
+// Each block:
+// * 16 bits: the number of tokens, `0` marks the end of the stream
+// * the code lengths of the literal/length code (`256 + (1 << ej)` symbols) and of the
+//   distance code (`2 * ei` symbols), each as 4 bits: `1..=12` is the length and `0` is
+//   followed by 4 bits with the number of further unused symbols (`0..=15`)
+// * the tokens, each is a symbol of the literal/length code:
+//   * `0..=255`: a literal
+//   * `256..`: a match with the length `symbol - 256 + p + 1`, followed by a symbol of the
+//     distance code (`2 * ei` symbols) and the extra bits of the distance (minus one), see
+//     distance_symbol
+// The codes are canonical huffman codes, all bits are written msb first.
+
+const MAX_BITS: usize = 12;
+const MAX_LITERAL_LENGTH: usize = 256 + (1 << 11);
+const MAX_DISTANCE: usize = 2 * 23;
+#[cfg(feature = "alloc")]
+const BLOCK_SIZE: usize = 1 << 14;
+
+// A canonical huffman code, for the decoder.
+struct HuffmanCode<const S: usize> {
+    // The number of symbols per code length.
+    count: [u16; MAX_BITS + 1],
+    // The used symbols, sorted by code length.
+    symbols: [u16; S],
+}
+
+impl<const S: usize> HuffmanCode<S> {
+    #[inline(always)]
+    fn new() -> Self {
+        HuffmanCode {
+            count: [0; MAX_BITS + 1],
+            symbols: [0; S],
+        }
+    }
+
+    // Returns false if the code lengths are over-subscribed.
+    fn build(&mut self, lengths: &[u8]) -> bool {
+        self.count = [0; MAX_BITS + 1];
+        for &length in lengths {
+            self.count[usize::from(length)] += 1;
+        }
+        self.count[0] = 0;
+        let mut left = 1i32;
+        let mut offsets = [0u16; MAX_BITS + 1];
+        for length in 1..=MAX_BITS {
+            left = (left << 1) - i32::from(self.count[length]);
+            if left < 0 {
+                return false;
+            }
+            if length < MAX_BITS {
+                offsets[length + 1] = offsets[length] + self.count[length];
+            }
+        }
+        for (symbol, &length) in lengths.iter().enumerate() {
+            if length != 0 {
+                self.symbols[usize::from(offsets[usize::from(length)])] = symbol as u16;
+                offsets[usize::from(length)] += 1;
+            }
+        }
+        true
+    }
+
+    // Read one symbol, `Ok(None)` is returned at the end of the input.
+    fn decode<R: Read, W>(
+        &self,
+        bit_reader: &mut BitReader<'_, R>,
+    ) -> Result<Option<usize>, LzssHuffmanError<R::Error, W>> {
+        let mut code = 0;
+        let mut first = 0;
+        let mut index = 0;
+        for length in 1..=MAX_BITS {
+            match bit_reader.read_bits(1).map_err(LzssError::ReadError)? {
+                None => return Ok(None),
+                Some(bit) => code |= bit as usize,
+            }
+            let count = usize::from(self.count[length]);
+            if code < first + count {
+                return Ok(Some(usize::from(self.symbols[index + code - first])));
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err(LzssHuffmanError::InvalidCode)
+    }
+}
+
+// Compute the code lengths (at most MAX_BITS) for the frequencies.
+#[cfg(feature = "alloc")]
+fn code_lengths(frequencies: &[u32], lengths: &mut [u8]) {
+    lengths.fill(0);
+    let mut leaves = frequencies
+        .iter()
+        .enumerate()
+        .filter(|(_, &frequency)| frequency > 0)
+        .map(|(symbol, &frequency)| (frequency, symbol))
+        .collect::<Vec<_>>();
+    match leaves.len() {
+        0 => return,
+        1 => {
+            lengths[leaves[0].1] = 1;
+            return;
+        }
+        _ => {}
+    }
+    leaves.sort_unstable();
+    let m = leaves.len();
+    loop {
+        // Two queues: the sorted leaves (0..m) and the created nodes (m..), both are sorted.
+        let mut weights = leaves
+            .iter()
+            .map(|&(f, _)| u64::from(f))
+            .collect::<Vec<_>>();
+        let mut parents = vec![0; 2 * m - 1];
+        let mut next_leaf = 0;
+        let mut next_node = m;
+        for node in m..2 * m - 1 {
+            let mut children = [0; 2];
+            for child in &mut children {
+                *child = if next_leaf < m
+                    && (next_node == node || weights[next_leaf] <= weights[next_node])
+                {
+                    next_leaf += 1;
+                    next_leaf - 1
+                } else {
+                    next_node += 1;
+                    next_node - 1
+                };
+            }
+            weights.push(weights[children[0]] + weights[children[1]]);
+            parents[children[0]] = node;
+            parents[children[1]] = node;
+        }
+        // The depth of each node, the parents are always created after the children.
+        let mut depths = vec![0u8; 2 * m - 1];
+        for node in (0..2 * m - 2).rev() {
+            depths[node] = depths[parents[node]] + 1;
+        }
+        if depths[..m]
+            .iter()
+            .all(|&depth| usize::from(depth) <= MAX_BITS)
+        {
+            for (&(_, symbol), &depth) in leaves.iter().zip(&depths) {
+                lengths[symbol] = depth;
+            }
+            return;
+        }
+        // Too long, flatten the frequencies and try again.
+        for (frequency, _) in &mut leaves {
+            *frequency = (*frequency + 1) / 2;
+        }
+        leaves.sort_unstable();
+    }
+}
+
+// Compute the canonical codes of the code lengths.
+#[cfg(feature = "alloc")]
+fn canonical_codes(lengths: &[u8], codes: &mut [u16]) {
+    let mut count = [0u16; MAX_BITS + 1];
+    for &length in lengths {
+        count[usize::from(length)] += 1;
+    }
+    count[0] = 0;
+    let mut next_code = [0u16; MAX_BITS + 1];
+    for length in 1..=MAX_BITS {
+        next_code[length] = (next_code[length - 1] + count[length - 1]) << 1;
+    }
+    for (&length, code) in lengths.iter().zip(codes) {
+        if length != 0 {
+            *code = next_code[usize::from(length)];
+            next_code[usize::from(length)] += 1;
+        }
+    }
+}
+
+// The symbol of the distance code, the number of extra bits and their value.
+// The distances 0..=3 have their own symbol, the others are grouped by the two top bits.
+#[cfg(feature = "alloc")]
+#[inline(always)]
+fn distance_symbol(distance: usize) -> (usize, usize, u32) {
+    if distance < 4 {
+        (distance, 0, 0)
+    } else {
+        let bits = (usize::BITS - distance.leading_zeros()) as usize;
+        (
+            2 * (bits - 1) + ((distance >> (bits - 2)) & 1),
+            bits - 2,
+            (distance & ((1 << (bits - 2)) - 1)) as u32,
+        )
+    }
+}
+
+impl LzssDyn {
+    /// Compress the input data into the output, with huffman coded tokens.
+    ///
+    /// The tokens are the same as of [`LzssDyn::compress`], but the literals, lengths and
+    /// the high bits of the distances are encoded with canonical huffman codes, which are
+    /// computed for each block of tokens. This is much smaller for text-heavy data, at the
+    /// cost of speed.
+    ///
+    /// The output is a separate format, it can only be decompressed with
+    /// [`LzssDyn::decompress_huffman`] (or [`LzssDyn::decompress_huffman_with_buffer`]),
+    /// which don't require any allocation.
+    ///
+    /// The buffer, with `2 * (1 << EI)` bytes, and the tokens of a block are allocated on the heap.
+    /// It will be asserted at runtime that there are no options.
+    ///
+    /// ```rust
+    /// # use lzss::{LzssDyn, LzssDynError, ResultLzssErrorVoidExt, SliceReader, VecWriter};
+    /// let my_lzss = LzssDyn::new(10, 4, 0x20)?;
+    /// let input = b"Example Data, Example Data";
+    /// let compressed = my_lzss
+    ///     .compress_huffman(SliceReader::new(input), VecWriter::with_capacity(30))
+    ///     .void_unwrap();
+    /// let output = my_lzss
+    ///     .decompress_huffman(SliceReader::new(&compressed), VecWriter::with_capacity(30))
+    ///     .unwrap();
+    /// assert_eq!(output, input);
+    /// # Ok::<(), LzssDynError>(())
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(any(feature = "alloc", feature = "std"))))]
+    #[cfg(feature = "alloc")]
+    pub fn compress_huffman<R: Read, W: Write>(
+        &self,
+        mut reader: R,
+        mut writer: W,
+    ) -> Result<W::Output, LzssError<R::Error, W::Error>> {
+        let mut buffer = vec![self.c; 2 * self.n()];
+        self.compress_huffman_internal(&mut reader, &mut writer, &mut buffer)?;
+        writer.finish().map_err(LzssError::WriteError)
+    }
+
+    /// Decompress the input data into the output, with huffman coded tokens
+    /// (see [`LzssDyn::compress_huffman`]).
+    ///
+    /// The buffer, with `1 << EI` bytes, is allocated on the heap.
+    #[cfg_attr(docsrs, doc(cfg(any(feature = "alloc", feature = "std"))))]
+    #[cfg(feature = "alloc")]
+    pub fn decompress_huffman<R: Read, W: Write>(
+        &self,
+        mut reader: R,
+        mut writer: W,
+    ) -> Result<W::Output, LzssHuffmanError<R::Error, W::Error>> {
+        let mut buffer = vec![self.c; self.n()];
+        self.decompress_huffman_internal(&mut reader, &mut writer, &mut buffer)?;
+        Ok(writer.finish().map_err(LzssError::WriteError)?)
+    }
+
+    /// Decompress the input data into the output, with huffman coded tokens
+    /// (see [`LzssDyn::compress_huffman`]).
+    ///
+    /// The code tables (about 7 KiB) are on the stack.
+    /// It will be asserted at runtime that the buffer is at least `1 << EI`.
+    pub fn decompress_huffman_with_buffer<R: Read, W: Write>(
+        &self,
+        mut reader: R,
+        mut writer: W,
+        buffer: &mut [u8],
+    ) -> Result<W::Output, LzssHuffmanError<R::Error, W::Error>> {
+        assert!(buffer.len() >= self.n());
+        buffer[..self.n()].fill(self.c);
+        self.decompress_huffman_internal(&mut reader, &mut writer, buffer)?;
+        Ok(writer.finish().map_err(LzssError::WriteError)?)
+    }
+
+    // The buffer handling and the search are the same as for compress, only the output differs.
+
+    // Allow many single char names, they are the ones of compress_window_internal.
+    #[allow(clippy::many_single_char_names)]
+    #[cfg(feature = "alloc")]
+    fn compress_huffman_internal<R: Read, W: Write>(
+        &self,
+        reader: &mut R,
+        writer: &mut W,
+        buffer: &mut [u8],
+    ) -> Result<(), LzssError<R::Error, W::Error>> {
+        assert!(self.options.is_empty(), "options are not supported");
+        let mut bit_writer = BitWriter::new(writer);
+        // The symbol of the literal/length code and the distance of a match.
+        let mut tokens = Vec::<(u16, u32)>::with_capacity(BLOCK_SIZE);
+
+        let mut buffer_end = self.n() - self.f();
+        while buffer_end < 2 * self.n() {
+            match reader.read().map_err(LzssError::ReadError)? {
+                None => break,
+                Some(data) => {
+                    set!(buffer, buffer_end, data);
+                    buffer_end += 1;
+                }
+            }
+        }
+
+        let mut r = self.n() - self.f();
+        let mut s = 0;
+        while r < buffer_end {
+            let f1 = self.f().min(buffer_end - r);
+            let c = get!(buffer, r);
+            let (x, mut y) = Self::find_match(buffer, s, r, f1);
+            if y <= self.p() {
+                tokens.push((u16::from(c), 0));
+                y = 1;
+            } else {
+                tokens.push(((256 + y - (self.p() + 1)) as u16, (r - x - 1) as u32));
+            }
+            if tokens.len() == BLOCK_SIZE {
+                self.write_huffman_block(&mut bit_writer, &tokens)
+                    .map_err(LzssError::WriteError)?;
+                tokens.clear();
+            }
+            r += y;
+            s += y;
+            if r >= self.n() * 2 - self.f() {
+                buffer.copy_within(self.n()..2 * self.n(), 0);
+                buffer_end -= self.n();
+                r -= self.n();
+                s -= self.n();
+                while buffer_end < 2 * self.n() {
+                    match reader.read().map_err(LzssError::ReadError)? {
+                        None => break,
+                        Some(data) => {
+                            set!(buffer, buffer_end, data);
+                            buffer_end += 1;
+                        }
+                    }
+                }
+            }
+        }
+        if !tokens.is_empty() {
+            self.write_huffman_block(&mut bit_writer, &tokens)
+                .map_err(LzssError::WriteError)?;
+        }
+        // The end of the stream
+        bit_writer
+            .write_bits(0, 16)
+            .map_err(LzssError::WriteError)?;
+        bit_writer.flush().map_err(LzssError::WriteError)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn write_huffman_block<W: Write>(
+        &self,
+        bit_writer: &mut BitWriter<'_, W>,
+        tokens: &[(u16, u32)],
+    ) -> Result<(), W::Error> {
+        let literal_length_size = 256 + (1 << self.ej);
+        let distance_size = 2 * self.ei;
+        let mut frequencies = vec![0; literal_length_size + distance_size];
+        for &(symbol, distance) in tokens {
+            frequencies[usize::from(symbol)] += 1;
+            if symbol >= 256 {
+                frequencies[literal_length_size + distance_symbol(distance as usize).0] += 1;
+            }
+        }
+        let mut lengths = vec![0; literal_length_size + distance_size];
+        code_lengths(
+            &frequencies[..literal_length_size],
+            &mut lengths[..literal_length_size],
+        );
+        code_lengths(
+            &frequencies[literal_length_size..],
+            &mut lengths[literal_length_size..],
+        );
+        let mut codes = vec![0; literal_length_size + distance_size];
+        canonical_codes(
+            &lengths[..literal_length_size],
+            &mut codes[..literal_length_size],
+        );
+        canonical_codes(
+            &lengths[literal_length_size..],
+            &mut codes[literal_length_size..],
+        );
+
+        bit_writer.write_bits(tokens.len() as u32, 16)?;
+        for lengths in [
+            &lengths[..literal_length_size],
+            &lengths[literal_length_size..],
+        ] {
+            let mut i = 0;
+            while i < lengths.len() {
+                if lengths[i] == 0 {
+                    let run = lengths[i..]
+                        .iter()
+                        .take(16)
+                        .take_while(|&&length| length == 0)
+                        .count();
+                    bit_writer.write_bits((run - 1) as u32, 8)?;
+                    i += run;
+                } else {
+                    bit_writer.write_bits(u32::from(lengths[i]), 4)?;
+                    i += 1;
+                }
+            }
+        }
+
+        for &(symbol, distance) in tokens {
+            let symbol = usize::from(symbol);
+            bit_writer.write_bits(u32::from(codes[symbol]), usize::from(lengths[symbol]))?;
+            if symbol >= 256 {
+                let (k, extra_bits, extra) = distance_symbol(distance as usize);
+                bit_writer.write_bits(
+                    u32::from(codes[literal_length_size + k]),
+                    usize::from(lengths[literal_length_size + k]),
+                )?;
+                if extra_bits > 0 {
+                    bit_writer.write_bits(extra, extra_bits)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn decompress_huffman_internal<R: Read, W: Write>(
+        &self,
+        reader: &mut R,
+        writer: &mut W,
+        buffer: &mut [u8],
+    ) -> Result<(), LzssHuffmanError<R::Error, W::Error>> {
+        assert!(self.options.is_empty(), "options are not supported");
+        let literal_length_size = 256 + (1 << self.ej);
+        let distance_size = 2 * self.ei;
+        let mut lengths = [0u8; MAX_LITERAL_LENGTH + MAX_DISTANCE];
+        let mut literal_length_code = HuffmanCode::<MAX_LITERAL_LENGTH>::new();
+        let mut distance_code = HuffmanCode::<MAX_DISTANCE>::new();
+        let mut bit_reader = BitReader::new(reader);
+
+        let mut r = self.n() - self.f();
+        loop {
+            let count = match bit_reader.read_bits(16).map_err(LzssError::ReadError)? {
+                None | Some(0) => return Ok(()),
+                Some(count) => count,
+            };
+            let mut i = 0;
+            while i < literal_length_size + distance_size {
+                match bit_reader.read_bits(4).map_err(LzssError::ReadError)? {
+                    None => return Ok(()),
+                    Some(0) => {
+                        let run = match bit_reader.read_bits(4).map_err(LzssError::ReadError)? {
+                            None => return Ok(()),
+                            Some(run) => run as usize + 1,
+                        };
+                        if i + run > literal_length_size + distance_size {
+                            return Err(LzssHuffmanError::InvalidCode);
+                        }
+                        lengths[i..i + run].fill(0);
+                        i += run;
+                    }
+                    Some(length) if length as usize <= MAX_BITS => {
+                        lengths[i] = length as u8;
+                        i += 1;
+                    }
+                    Some(_) => return Err(LzssHuffmanError::InvalidCode),
+                }
+            }
+            if !literal_length_code.build(&lengths[..literal_length_size])
+                || !distance_code
+                    .build(&lengths[literal_length_size..literal_length_size + distance_size])
+            {
+                return Err(LzssHuffmanError::InvalidCode);
+            }
+
+            for _ in 0..count {
+                let symbol = match literal_length_code.decode(&mut bit_reader)? {
+                    None => return Ok(()),
+                    Some(symbol) => symbol,
+                };
+                if symbol < 256 {
+                    writer.write(symbol as u8).map_err(LzssError::WriteError)?;
+                    set!(buffer, r, symbol as u8);
+                    r = (r + 1) & (self.n() - 1);
+                    continue;
+                }
+                let k = match distance_code.decode(&mut bit_reader)? {
+                    None => return Ok(()),
+                    Some(k) => k,
+                };
+                let distance = if k < 4 {
+                    k
+                } else {
+                    let extra_bits = k / 2 - 1;
+                    match bit_reader
+                        .read_bits(extra_bits)
+                        .map_err(LzssError::ReadError)?
+                    {
+                        None => return Ok(()),
+                        Some(extra) => ((2 | (k & 1)) << extra_bits) | extra as usize,
+                    }
+                };
+                let i = r.wrapping_sub(distance + 1) & (self.n() - 1);
+                for k in 0..=symbol - 256 + self.p() {
+                    let b = get!(buffer, (i + k) & (self.n() - 1));
+                    writer.write(b).map_err(LzssError::WriteError)?;
+                    set!(buffer, r, b);
+                    r = (r + 1) & (self.n() - 1);
+                }
+            }
+        }
+    }
+}
+
+/// The error returned by [`LzssDyn::decompress_huffman`].
+#[derive(Debug, Eq, PartialEq)]
+pub enum LzssHuffmanError<R, W> {
+    /// Contains the read or write error.
+    Lzss(LzssError<R, W>),
+    /// The code lengths of a block or a code in the data are invalid.
+    InvalidCode,
+}
+
+impl<R, W> From<LzssError<R, W>> for LzssHuffmanError<R, W> {
+    #[inline]
+    fn from(error: LzssError<R, W>) -> Self {
+        LzssHuffmanError::Lzss(error)
+    }
+}
+
+impl<R: Display, W: Display> core::fmt::Display for LzssHuffmanError<R, W> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            LzssHuffmanError::Lzss(error) => error.fmt(f),
+            LzssHuffmanError::InvalidCode => f.write_str("Invalid huffman code"),
+        }
+    }
+}
+
+/// Implementation of [`Error`](std::error::Error) for [`LzssHuffmanError`]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[cfg(feature = "std")]
+impl<R, W> std::error::Error for LzssHuffmanError<R, W>
+where
+    R: std::error::Error + 'static,
+    W: std::error::Error + 'static,
+{
+    #[inline]
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LzssHuffmanError::Lzss(error) => error.source(),
+            LzssHuffmanError::InvalidCode => None,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use crate::dynamic::{LzssDyn, LzssHuffmanError};
+    use crate::slice::SliceReader;
+    use crate::vec::VecWriter;
+    use crate::void::ResultLzssErrorVoidExt;
+
+    #[test]
+    fn test_huffman() {
+        let big_test_data = include_bytes!("huffman.rs");
+        for (ei, ej) in [(10, 4), (12, 6), (12, 11)] {
+            let lzss = LzssDyn::new(ei, ej, 0x20).unwrap();
+            for input in [&big_test_data[..], b"", b"a", b"aaaaaaaaaaaaaaaaaaaaaaa"] {
+                let compressed = lzss
+                    .compress_huffman(
+                        SliceReader::new(input),
+                        VecWriter::with_capacity(input.len()),
+                    )
+                    .void_unwrap();
+                let mut buffer = [0; 1 << 12];
+                let output = lzss
+                    .decompress_huffman_with_buffer(
+                        SliceReader::new(&compressed),
+                        VecWriter::with_capacity(input.len()),
+                        &mut buffer,
+                    )
+                    .unwrap();
+                assert_eq!(output, input);
+            }
+        }
+        // better than the fixed width tokens
+        let lzss = LzssDyn::new(12, 4, 0x20).unwrap();
+        let compressed = lzss
+            .compress_huffman(
+                SliceReader::new(big_test_data),
+                VecWriter::with_capacity(big_test_data.len()),
+            )
+            .void_unwrap();
+        let plain = lzss
+            .compress(
+                SliceReader::new(big_test_data),
+                VecWriter::with_capacity(big_test_data.len()),
+            )
+            .void_unwrap();
+        assert!(compressed.len() * 10 < plain.len() * 9);
+        // over-subscribed code lengths: three codes with the length 1
+        let mut invalid = vec![0x00, 0x01, 0x11, 0x10];
+        invalid.extend_from_slice(&[0xf0; 18]);
+        invalid.push(0x40);
+        assert_eq!(
+            lzss.decompress_huffman(SliceReader::new(&invalid), VecWriter::with_capacity(10)),
+            Err(LzssHuffmanError::InvalidCode)
+        );
+    }
+}
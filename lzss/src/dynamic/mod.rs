@@ -1,8 +1,25 @@
 use crate::error::LzssError;
+use crate::options::LzssOptions;
 use crate::read_write::{Read, Write};
+use crate::slice::SliceWriteError;
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use alloc::vec::Vec;
+use void::Void;
+
+pub use delta::LzssDeltaError;
+pub use huffman::LzssHuffmanError;
+pub use word::LzssWordError;
 
 mod compress;
+mod compress_in_place;
+mod compress_ring;
+mod compress_slice;
 mod decompress;
+mod decompress_slice;
+mod delta;
+mod huffman;
+mod parse;
+mod word;
 
 /// Dynamic parameters for de-/compression (see [Lzss](crate::Lzss) for compile-time parameters).
 ///
@@ -10,12 +27,17 @@ mod decompress;
 /// * `ei` - The number of bits in the offset, usually `10..13`
 /// * `ej` - The number of bits in the length, usually `4..5`
 /// * `c` - The initial fill byte of the buffer, usually `0x20` (space)
+/// * `p` - If a match is not longer than `p` a literal is used instead, usually
+///   `(1 + ei + ej) / 9` (see [`LzssDyn::new_with_p`], where `0` selects this default)
+/// * `options` - The [`LzssOptions`], none by default (see [`LzssDyn::with_options`])
 ///
 /// # Restrictions
 /// * `ej` must be larger than `0`
 /// * `ei` must be larger than `ej`
 /// * `ei + ej` must be at least 8
 /// * `ei + ej` must be 24 or less
+/// * `1 + ei + ej` must be at most `9 * (p + 1)`, i.e. a match must not take more than 9 bits per byte
+/// * `(1 << ej) + p` must be less than `1 << ei`
 ///
 /// # Example
 /// ```rust
@@ -29,20 +51,43 @@ mod decompress;
 /// assert_eq!(result.void_unwrap().len(), 14); // the output is 14 bytes long
 /// # Ok::<(), LzssDynError>(())
 /// ```
-#[derive(Copy, Clone, Eq, PartialEq)]
+///
+/// The parameters can also be parsed from and printed as `ei,ej,c` (e.g. `10,4,0x20`)
+/// or `ei,ej,c,p` (e.g. `12,4,0x20,2`), see [`FromStr`](core::str::FromStr).
+///
+/// With the `serde` feature the parameters can be (de-)serialized, the deserialized
+/// parameters are checked with [`LzssDyn::new`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "LzssDynUnchecked"))]
 pub struct LzssDyn {
     pub(crate) ei: usize,
     pub(crate) ej: usize,
     pub(crate) c: u8,
+    pub(crate) p: usize,
+    pub(crate) options: LzssOptions,
 }
 
 impl LzssDyn {
-    /// Create new Lzss parameters.
+    /// Create new Lzss parameters, `p` is `(1 + ei + ej) / 9`.
     ///
     /// If the parameter are not valid (see above) an error is returned.
     ///
     /// For creating a const see [`Lzss::as_dyn`](crate::generic::Lzss::as_dyn).
     pub fn new(ei: usize, ej: usize, c: u8) -> Result<Self, LzssDynError> {
+        Self::new_with_p(ei, ej, c, (1 + ei + ej) / 9)
+    }
+
+    /// Create new Lzss parameters with a custom `p`.
+    ///
+    /// This is required to interoperate with other implementations, which are using
+    /// a different threshold (for example `p = 2` with `ei = 12` and `ej = 4`).
+    ///
+    /// As for `P` of [`Lzss`](crate::Lzss), `p = 0` selects the default `(1 + ei + ej) / 9`.
+    ///
+    /// If the parameter are not valid (see above) an error is returned.
+    pub fn new_with_p(ei: usize, ej: usize, c: u8, p: usize) -> Result<Self, LzssDynError> {
+        let p = if p == 0 { (1 + ei + ej) / 9 } else { p };
         if ej == 0 {
             Err(LzssDynError::EjIsZero)
         } else if ej >= ei {
@@ -51,8 +96,18 @@ impl LzssDyn {
             Err(LzssDynError::EiEjToSmall)
         } else if ei + ej > 24 || (ei as u32) + 1 >= usize::BITS {
             Err(LzssDynError::EiEjToLarge)
+        } else if 1 + ei + ej > 9 * (p + 1) {
+            Err(LzssDynError::PToSmall)
+        } else if (1 << ej) + p >= 1 << ei {
+            Err(LzssDynError::PToLarge)
         } else {
-            Ok(LzssDyn { ei, ej, c })
+            Ok(LzssDyn {
+                ei,
+                ej,
+                c,
+                p,
+                options: LzssOptions::NONE,
+            })
         }
     }
 
@@ -77,16 +132,33 @@ impl LzssDyn {
         self.c
     }
 
+    /// Get the p parameter.
     #[inline(always)]
     #[must_use]
-    pub(crate) const fn n(&self) -> usize {
-        1 << self.ei
+    pub const fn p(&self) -> usize {
+        self.p
+    }
+
+    /// Get the options.
+    #[inline(always)]
+    #[must_use]
+    pub const fn options(&self) -> LzssOptions {
+        self.options
+    }
+
+    /// Replace the options, see [`LzssOptions`].
+    ///
+    /// The options are not part of the checked parameters, thus they can be set afterwards.
+    #[inline(always)]
+    #[must_use]
+    pub const fn with_options(self, options: LzssOptions) -> Self {
+        LzssDyn { options, ..self }
     }
 
     #[inline(always)]
     #[must_use]
-    pub(crate) const fn p(&self) -> usize {
-        (1 + self.ei + self.ej) / 9
+    pub(crate) const fn n(&self) -> usize {
+        1 << self.ei
     }
 
     #[inline(always)]
@@ -95,6 +167,19 @@ impl LzssDyn {
         (1 << self.ej) + self.p()
     }
 
+    #[inline(always)]
+    #[must_use]
+    pub(crate) const fn min_gap_size(&self) -> usize {
+        self.p() + 4
+    }
+
+    // The width of the offset after `seen` bytes, see LzssOptions::ADAPTIVE_OFFSET.
+    #[inline(always)]
+    #[must_use]
+    pub(crate) const fn offset_bits(&self, seen: usize) -> usize {
+        self.options.offset_bits(self.ei, self.ej, seen)
+    }
+
     /// Compress the input data into the output.
     ///
     /// The buffer, with `2 * (1 << EI)` bytes, is allocated on the heap.
@@ -106,7 +191,7 @@ impl LzssDyn {
         mut writer: W,
     ) -> Result<W::Output, LzssError<R::Error, W::Error>> {
         let mut buffer = vec![self.c; 2 * self.n()];
-        self.compress_internal(&mut reader, &mut writer, &mut buffer)?;
+        self.compress_internal(&mut reader, &mut writer, &mut buffer, self.n())?;
         writer.finish().map_err(LzssError::WriteError)
     }
 
@@ -121,7 +206,77 @@ impl LzssDyn {
     ) -> Result<W::Output, LzssError<R::Error, W::Error>> {
         assert!(buffer.len() >= 2 * self.n());
         buffer[..self.n() - self.f()].fill(self.c);
-        self.compress_internal(&mut reader, &mut writer, buffer)?;
+        self.compress_internal(&mut reader, &mut writer, buffer, self.n())?;
+        writer.finish().map_err(LzssError::WriteError)
+    }
+
+    /// Compress the input data into the output, matches are at most `max_distance` bytes back.
+    ///
+    /// With [`LzssOptions::RELATIVE`] the output can be decompressed with a window of
+    /// `max_distance` bytes (rounded up to a power of two), see [`LzssDyn::decompress_with_window`].
+    /// The format is unchanged, any decompressor can read it.
+    ///
+    /// The buffer, with `2 * (1 << EI)` bytes, is allocated on the heap.
+    #[cfg_attr(docsrs, doc(cfg(any(feature = "alloc", feature = "std"))))]
+    #[cfg(feature = "alloc")]
+    pub fn compress_with_max_distance<R: Read, W: Write>(
+        &self,
+        mut reader: R,
+        mut writer: W,
+        max_distance: usize,
+    ) -> Result<W::Output, LzssError<R::Error, W::Error>> {
+        let mut buffer = vec![self.c; 2 * self.n()];
+        self.compress_internal(&mut reader, &mut writer, &mut buffer, max_distance)?;
+        writer.finish().map_err(LzssError::WriteError)
+    }
+
+    /// Compress the input data into the output, with a ring buffer.
+    ///
+    /// The output is identical to [`LzssDyn::compress`], but only half the buffer is
+    /// required and no data has to be moved. Though the search is a bit slower.
+    ///
+    /// The buffer, with `1 << EI` bytes, is allocated on the heap.
+    #[cfg_attr(docsrs, doc(cfg(any(feature = "alloc", feature = "std"))))]
+    #[cfg(feature = "alloc")]
+    pub fn compress_ring<R: Read, W: Write>(
+        &self,
+        mut reader: R,
+        mut writer: W,
+    ) -> Result<W::Output, LzssError<R::Error, W::Error>> {
+        let mut buffer = vec![self.c; self.n()];
+        self.compress_ring_internal(&mut reader, &mut writer, &mut buffer)?;
+        writer.finish().map_err(LzssError::WriteError)
+    }
+
+    /// Compress the input data into the output, with a ring buffer.
+    ///
+    /// The output is identical to [`LzssDyn::compress_with_buffer`], but only half the buffer is
+    /// required and no data has to be moved. Though the search is a bit slower.
+    ///
+    /// It will be asserted at runtime that the buffer is at least `1 << EI`.
+    pub fn compress_ring_with_buffer<R: Read, W: Write>(
+        &self,
+        mut reader: R,
+        mut writer: W,
+        buffer: &mut [u8],
+    ) -> Result<W::Output, LzssError<R::Error, W::Error>> {
+        assert!(buffer.len() >= self.n());
+        buffer[..self.n() - self.f()].fill(self.c);
+        self.compress_ring_internal(&mut reader, &mut writer, buffer)?;
+        writer.finish().map_err(LzssError::WriteError)
+    }
+
+    /// Compress the input slice into the output.
+    ///
+    /// The output is identical to [`LzssDyn::compress`], but no buffer is required
+    /// since the matches are searched directly in the input.
+    pub fn compress_slice<W: Write>(
+        &self,
+        input: &[u8],
+        mut writer: W,
+    ) -> Result<W::Output, LzssError<Void, W::Error>> {
+        self.compress_slice_internal(input, &mut writer)
+            .map_err(LzssError::WriteError)?;
         writer.finish().map_err(LzssError::WriteError)
     }
 
@@ -154,9 +309,206 @@ impl LzssDyn {
         self.decompress_internal(&mut reader, &mut writer, buffer)?;
         writer.finish().map_err(LzssError::WriteError)
     }
+
+    /// Decompress the input data into the output, with a window smaller than `1 << EI`.
+    ///
+    /// This requires [`LzssOptions::RELATIVE`] (or [`LzssOptions::ADAPTIVE_OFFSET`]) and no
+    /// distance in the input may be larger than the window, e.g. it was compressed with
+    /// [`LzssDyn::compress_with_max_distance`]. Larger distances are not detected, the output is wrong then.
+    ///
+    /// It will be asserted at runtime that the options are relative and that the window is
+    /// a power of two, but not larger than `1 << EI`.
+    pub fn decompress_with_window<R: Read, W: Write>(
+        &self,
+        mut reader: R,
+        mut writer: W,
+        window: &mut [u8],
+    ) -> Result<W::Output, LzssError<R::Error, W::Error>> {
+        assert!(self.options.is_relative());
+        assert!(window.len().is_power_of_two() && window.len() <= self.n());
+        window.fill(self.c);
+        self.decompress_window_internal(&mut reader, &mut writer, window)?;
+        writer.finish().map_err(LzssError::WriteError)
+    }
+
+    /// Decompress the input data into the output slice.
+    ///
+    /// No buffer is required, the already written output is used instead.
+    ///
+    /// Returns the number of written bytes, or [`SliceWriteError`] in case the output is too small.
+    pub fn decompress_to_slice<R: Read>(
+        &self,
+        mut reader: R,
+        output: &mut [u8],
+    ) -> Result<usize, LzssError<R::Error, SliceWriteError>> {
+        self.decompress_to_slice_internal(&mut reader, output)
+    }
+
+    /// Decompress the input data into a new vector.
+    ///
+    /// No buffer is required, the already written output is used instead.
+    #[cfg_attr(docsrs, doc(cfg(any(feature = "alloc", feature = "std"))))]
+    #[cfg(feature = "alloc")]
+    pub fn decompress_to_vec<R: Read>(
+        &self,
+        mut reader: R,
+    ) -> Result<Vec<u8>, LzssError<R::Error, Void>> {
+        let mut output = Vec::new();
+        self.decompress_to_vec_internal(&mut reader, &mut output)
+            .map_err(LzssError::ReadError)?;
+        Ok(output)
+    }
+
+    /// Compress, the input and output is in the same slice.
+    ///
+    /// The input is located at `io[offset..]`.
+    /// When there is enough space in the slice then the result will be `(size, None)`.
+    /// And the output is located at `io[0..size]`.
+    ///
+    /// If there is not enough space in the slice, i.e. the output (or buffer) would overwrite
+    /// the input, then the result will be `(size, Some(new_offset))`, the already compressed
+    /// data is in `io[0..size]` and the not yet compressed data is in `io[new_offset..]`.
+    ///
+    /// Even when the compression fails due to space the data is recoverable,
+    /// see [`LzssDyn::compress_in_place_continue_with_buffer`].
+    ///
+    /// The minimum offset is [`LzssDyn::min_offset`], though if the offset is `min_offset() + input_size/8`
+    /// then the compression can't fail.
+    pub fn compress_in_place(&self, io: &mut [u8], offset: usize) -> (usize, Option<usize>) {
+        self.compress_in_place_internal(io, offset)
+    }
+
+    /// The minimal offset when using `compress_in_place`.
+    ///
+    /// It's a little less than `1 << EI`.
+    #[inline(always)]
+    #[must_use]
+    pub const fn min_offset(&self) -> usize {
+        (self.n() - self.f()) + self.min_gap_size()
+    }
+
+    /// Continue a `compress_in_place` which returned `(size, Some(new_offset))`.
+    ///
+    /// The not yet compressed data at `io[new_offset..]` is compressed into the writer.
+    /// Since the last byte of the already compressed data may be incomplete, it is written
+    /// again, i.e. the complete compressed data is `io[0..size - 1]` (or nothing if `size` is `0`)
+    /// followed by the output of the writer.
+    ///
+    /// `io[0..size]` and `io[new_offset..]` must be the unaltered data from `compress_in_place`,
+    /// as well as the data in front of `new_offset` (about `1 << EI` bytes).
+    ///
+    /// The buffer, with `2 * (1 << EI)` bytes, is allocated on the heap.
+    #[cfg_attr(docsrs, doc(cfg(any(feature = "alloc", feature = "std"))))]
+    #[cfg(feature = "alloc")]
+    pub fn compress_in_place_continue<W: Write>(
+        &self,
+        io: &[u8],
+        size: usize,
+        new_offset: usize,
+        mut writer: W,
+    ) -> Result<W::Output, LzssError<Void, W::Error>> {
+        let mut buffer = vec![self.c; 2 * self.n()];
+        self.compress_in_place_continue_internal(io, size, new_offset, &mut writer, &mut buffer)?;
+        writer.finish().map_err(LzssError::WriteError)
+    }
+
+    /// Continue a `compress_in_place` which returned `(size, Some(new_offset))`.
+    ///
+    /// The not yet compressed data at `io[new_offset..]` is compressed into the writer.
+    /// Since the last byte of the already compressed data may be incomplete, it is written
+    /// again, i.e. the complete compressed data is `io[0..size - 1]` (or nothing if `size` is `0`)
+    /// followed by the output of the writer.
+    ///
+    /// `io[0..size]` and `io[new_offset..]` must be the unaltered data from `compress_in_place`,
+    /// as well as the data in front of `new_offset` (about `1 << EI` bytes).
+    ///
+    /// It will be asserted at runtime that the buffer is at least `2 * (1 << EI)`.
+    pub fn compress_in_place_continue_with_buffer<W: Write>(
+        &self,
+        io: &[u8],
+        size: usize,
+        new_offset: usize,
+        mut writer: W,
+        buffer: &mut [u8],
+    ) -> Result<W::Output, LzssError<Void, W::Error>> {
+        assert!(buffer.len() >= 2 * self.n());
+        self.compress_in_place_continue_internal(io, size, new_offset, &mut writer, buffer)?;
+        writer.finish().map_err(LzssError::WriteError)
+    }
+
+    /// Decompress, the input and output is in the same slice.
+    ///
+    /// The input is located at `io[compressed_offset..compressed_offset + compressed_len]`
+    /// and the output is written to `io[0..size]`, the result is `Ok(size)`.
+    ///
+    /// When the output would overwrite not yet read input (or the input is not within the slice)
+    /// then [`SliceWriteError`] is returned.
+    ///
+    /// Placing the input at the end of a slice with the size `uncompressed_len +
+    /// my_lzss.decompress_in_place_margin(uncompressed_len)` ensures that this can't happen.
+    pub fn decompress_in_place(
+        &self,
+        io: &mut [u8],
+        compressed_offset: usize,
+        compressed_len: usize,
+    ) -> Result<usize, SliceWriteError> {
+        self.decompress_in_place_internal(io, compressed_offset, compressed_len)
+    }
+
+    /// The number of bytes which the slice for `decompress_in_place` has to be larger than
+    /// the decompressed data.
+    ///
+    /// With [`LzssOptions::END_MARKER`] it includes the end marker, but not the flush
+    /// markers of [`LzssCompressor::flush`](crate::LzssCompressor::flush) (up to 4 bytes each).
+    #[must_use]
+    pub const fn decompress_in_place_margin(&self, uncompressed_len: usize) -> usize {
+        decompress_in_place_margin(self.options, uncompressed_len)
+    }
+}
+
+// The margin for decompress_in_place, the end marker is read after all data is written.
+#[inline(always)]
+pub(crate) const fn decompress_in_place_margin(
+    options: LzssOptions,
+    uncompressed_len: usize,
+) -> usize {
+    if options.contains(LzssOptions::END_MARKER) {
+        uncompressed_len / 8 + 5
+    } else {
+        uncompressed_len / 8 + 1
+    }
+}
+
+/// Helper for deserializing [`LzssDyn`], the parameters are checked through [`LzssDyn::new_with_p`].
+///
+/// The fields have the same types as in [`LzssDyn`], thus non-self-describing formats can read
+/// back what they wrote. The `p` and `options` may be omitted in self-describing formats, for
+/// compatibility with data serialized before they were added, a missing (or `0`) `p` is
+/// `(1 + ei + ej) / 9`.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+#[serde(rename = "LzssDyn")]
+struct LzssDynUnchecked {
+    ei: usize,
+    ej: usize,
+    c: u8,
+    #[serde(default)]
+    p: usize,
+    #[serde(default)]
+    options: LzssOptions,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<LzssDynUnchecked> for LzssDyn {
+    type Error = LzssDynError;
+
+    fn try_from(value: LzssDynUnchecked) -> Result<Self, Self::Error> {
+        LzssDyn::new_with_p(value.ei, value.ej, value.c, value.p)
+            .map(|lzss| lzss.with_options(value.options))
+    }
 }
 
-/// The error returned by [`LzssDyn::new`].
+/// The error returned by [`LzssDyn::new`], [`LzssDyn::new_with_p`] and when parsing a [`LzssDyn`].
 #[derive(Debug)]
 pub enum LzssDynError {
     /// Invalid EJ, must be larger than 0.
@@ -167,6 +519,22 @@ pub enum LzssDynError {
     EiEjToSmall,
     /// Invalid EI, EJ, both together must be 24 or less.
     EiEjToLarge,
+    /// Invalid P, a match must not take more than 9 bits per byte.
+    PToSmall,
+    /// Invalid P, `(1 << EJ) + P` must be less than `1 << EI`.
+    PToLarge,
+    /// Invalid syntax, expected `ei,ej,c` or `ei,ej,c,p`, optionally followed by options.
+    ParseSyntax,
+    /// Invalid EI, not a number.
+    ParseEi,
+    /// Invalid EJ, not a number.
+    ParseEj,
+    /// Invalid C, not a decimal or hexadecimal (with `0x` prefix) byte.
+    ParseC,
+    /// Invalid P, not a number.
+    ParseP,
+    /// Invalid options, unknown name or bit.
+    UnknownOptions,
 }
 
 impl core::fmt::Display for LzssDynError {
@@ -180,6 +548,22 @@ impl core::fmt::Display for LzssDynError {
             LzssDynError::EiEjToLarge => {
                 f.write_str("Invalid EI, EJ, both together must be 24 or less")
             }
+            LzssDynError::PToSmall => {
+                f.write_str("Invalid P, a match must not take more than 9 bits per byte")
+            }
+            LzssDynError::PToLarge => {
+                f.write_str("Invalid P, (1 << EJ) + P must be less than 1 << EI")
+            }
+            LzssDynError::ParseSyntax => f.write_str(
+                "Invalid syntax, expected ei,ej,c or ei,ej,c,p, optionally followed by options",
+            ),
+            LzssDynError::ParseEi => f.write_str("Invalid EI, not a number"),
+            LzssDynError::ParseEj => f.write_str("Invalid EJ, not a number"),
+            LzssDynError::ParseC => {
+                f.write_str("Invalid C, not a decimal or hexadecimal (with 0x prefix) byte")
+            }
+            LzssDynError::ParseP => f.write_str("Invalid P, not a number"),
+            LzssDynError::UnknownOptions => f.write_str("Invalid options, unknown name or bit"),
         }
     }
 }
@@ -191,11 +575,14 @@ impl std::error::Error for LzssDynError {}
 
 #[cfg(all(test, feature = "alloc"))]
 mod tests {
-    use crate::dynamic::LzssDyn;
+    use crate::dynamic::{LzssDyn, LzssDynError};
     use crate::generic::Lzss;
-    use crate::slice::SliceReader;
+    use crate::options::LzssOptions;
+    use crate::slice::{SliceReader, SliceWriteError};
     use crate::vec::VecWriter;
-    use crate::void::ResultLzssErrorVoidExt;
+    use crate::void::{ResultLzssErrorVoidExt, ResultLzssErrorVoidReadExt};
+    #[cfg(not(feature = "std"))]
+    use alloc::vec::Vec;
 
     const TEST_LZSS: LzssDyn = Lzss::<10, 4, 0x20, { 1 << 10 }, { 2 << 10 }>::as_dyn();
 
@@ -245,6 +632,21 @@ mod tests {
             )
             .void_unwrap();
         assert_eq!(output2.as_slice(), big_test_data);
+        // compress_ring
+        let mut buffer = [0u8; 1 << 10];
+        let output3 = TEST_LZSS
+            .compress_ring_with_buffer(
+                SliceReader::new(big_test_data),
+                VecWriter::with_capacity(big_test_data.len()),
+                &mut buffer,
+            )
+            .void_unwrap();
+        assert_eq!(output1, output3);
+        // compress_slice
+        let output4 = TEST_LZSS
+            .compress_slice(big_test_data, VecWriter::with_capacity(big_test_data.len()))
+            .void_unwrap();
+        assert_eq!(output1, output4);
     }
 
     #[test]
@@ -272,4 +674,643 @@ mod tests {
             .void_unwrap();
         assert_eq!(output.as_slice(), COMPRESSED_DATA);
     }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("10,4,0x20".parse::<LzssDyn>().unwrap(), TEST_LZSS);
+        assert_eq!(" 10 , 4 , 32 ".parse::<LzssDyn>().unwrap(), TEST_LZSS);
+        assert_eq!(format!("{TEST_LZSS}"), "10,4,0x20");
+        assert!(matches!(
+            "10,4".parse::<LzssDyn>(),
+            Err(LzssDynError::ParseSyntax)
+        ));
+        assert_eq!("10,4,0x20,1".parse::<LzssDyn>().unwrap(), TEST_LZSS);
+        assert_eq!(
+            "12,4,0x20,2".parse::<LzssDyn>().unwrap(),
+            LzssDyn::new_with_p(12, 4, 0x20, 2).unwrap()
+        );
+        assert_eq!(
+            format!("{}", LzssDyn::new_with_p(12, 4, 0x20, 2).unwrap()),
+            "12,4,0x20,2"
+        );
+        assert!(matches!(
+            "10,4,0x20,1,2".parse::<LzssDyn>(),
+            Err(LzssDynError::ParseSyntax)
+        ));
+        assert_eq!(
+            "10,4,0x20,relative".parse::<LzssDyn>().unwrap(),
+            TEST_LZSS.with_options(LzssOptions::RELATIVE)
+        );
+        assert_eq!(
+            "12,4,0x20,2,relative".parse::<LzssDyn>().unwrap(),
+            LzssDyn::new_with_p(12, 4, 0x20, 2)
+                .unwrap()
+                .with_options(LzssOptions::RELATIVE)
+        );
+        assert_eq!(
+            format!("{}", TEST_LZSS.with_options(LzssOptions::RELATIVE)),
+            "10,4,0x20,relative"
+        );
+        assert!(matches!(
+            "10,4,0x20,1,other".parse::<LzssDyn>(),
+            Err(LzssDynError::UnknownOptions)
+        ));
+        assert!(matches!(
+            "10,4,0x20,x".parse::<LzssDyn>(),
+            Err(LzssDynError::ParseP)
+        ));
+        assert!(matches!(
+            "13,5,0x20,1".parse::<LzssDyn>(),
+            Err(LzssDynError::PToSmall)
+        ));
+        // p = 0 is the default, as for the generic P
+        assert_eq!(
+            "13,5,0x20,0".parse::<LzssDyn>().unwrap(),
+            LzssDyn::new(13, 5, 0x20).unwrap()
+        );
+        assert_eq!(
+            LzssDyn::new_with_p(13, 5, 0x20, 0).unwrap(),
+            Lzss::<13, 5, 0x20, { 1 << 13 }, { 2 << 13 }, 0>::as_dyn()
+        );
+        assert!(matches!(
+            "5,3,0x20,24".parse::<LzssDyn>(),
+            Err(LzssDynError::PToLarge)
+        ));
+        assert!(matches!(
+            "a,4,0x20".parse::<LzssDyn>(),
+            Err(LzssDynError::ParseEi)
+        ));
+        assert!(matches!(
+            "10,-4,0x20".parse::<LzssDyn>(),
+            Err(LzssDynError::ParseEj)
+        ));
+        assert!(matches!(
+            "10,4,0x200".parse::<LzssDyn>(),
+            Err(LzssDynError::ParseC)
+        ));
+        assert!(matches!(
+            "10,0,0x20".parse::<LzssDyn>(),
+            Err(LzssDynError::EjIsZero)
+        ));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        use serde_test::{assert_de_tokens, assert_de_tokens_error, assert_tokens, Token};
+
+        assert_tokens(
+            &TEST_LZSS,
+            &[
+                Token::Struct {
+                    name: "LzssDyn",
+                    len: 5,
+                },
+                Token::Str("ei"),
+                Token::U64(10),
+                Token::Str("ej"),
+                Token::U64(4),
+                Token::Str("c"),
+                Token::U8(0x20),
+                Token::Str("p"),
+                Token::U64(1),
+                Token::Str("options"),
+                Token::U8(0),
+                Token::StructEnd,
+            ],
+        );
+        assert_de_tokens(
+            &TEST_LZSS,
+            &[
+                Token::Struct {
+                    name: "LzssDyn",
+                    len: 3,
+                },
+                Token::Str("ei"),
+                Token::U64(10),
+                Token::Str("ej"),
+                Token::U64(4),
+                Token::Str("c"),
+                Token::U8(0x20),
+                Token::StructEnd,
+            ],
+        );
+        assert_de_tokens_error::<LzssDyn>(
+            &[
+                Token::Struct {
+                    name: "LzssDyn",
+                    len: 3,
+                },
+                Token::Str("ei"),
+                Token::U64(4),
+                Token::Str("ej"),
+                Token::U64(4),
+                Token::Str("c"),
+                Token::U8(0x20),
+                Token::StructEnd,
+            ],
+            "Invalid EI, must be larger than EJ",
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_options() {
+        use serde_test::{assert_de_tokens, assert_de_tokens_error, assert_tokens, Token};
+
+        assert_tokens(
+            &LzssDyn::new_with_p(12, 4, 0x20, 2)
+                .unwrap()
+                .with_options(LzssOptions::RELATIVE | LzssOptions::END_MARKER),
+            &[
+                Token::Struct {
+                    name: "LzssDyn",
+                    len: 5,
+                },
+                Token::Str("ei"),
+                Token::U64(12),
+                Token::Str("ej"),
+                Token::U64(4),
+                Token::Str("c"),
+                Token::U8(0x20),
+                Token::Str("p"),
+                Token::U64(2),
+                Token::Str("options"),
+                Token::U8(3),
+                Token::StructEnd,
+            ],
+        );
+        assert_de_tokens(
+            &TEST_LZSS.with_options(LzssOptions::RELATIVE),
+            &[
+                Token::Struct {
+                    name: "LzssDyn",
+                    len: 4,
+                },
+                Token::Str("ei"),
+                Token::U64(10),
+                Token::Str("ej"),
+                Token::U64(4),
+                Token::Str("c"),
+                Token::U8(0x20),
+                Token::Str("options"),
+                Token::U8(1),
+                Token::StructEnd,
+            ],
+        );
+        assert_de_tokens_error::<LzssDyn>(
+            &[
+                Token::Struct {
+                    name: "LzssDyn",
+                    len: 4,
+                },
+                Token::Str("ei"),
+                Token::U64(10),
+                Token::Str("ej"),
+                Token::U64(4),
+                Token::Str("c"),
+                Token::U8(0x20),
+                Token::Str("options"),
+                Token::U8(0x80),
+                Token::StructEnd,
+            ],
+            "Invalid options, unknown name or bit",
+        );
+    }
+
+    #[test]
+    fn test_relative() {
+        let big_test_data = include_bytes!("mod.rs");
+        let relative = TEST_LZSS.with_options(LzssOptions::RELATIVE);
+        let output1 = relative
+            .compress(
+                SliceReader::new(big_test_data),
+                VecWriter::with_capacity(big_test_data.len()),
+            )
+            .void_unwrap();
+        // the buffer in front of the input is not used, thus a different C works
+        let mut buffer = [0xaa; 1 << 10];
+        let output2 = Lzss::<10, 4, 0x00, { 1 << 10 }, { 2 << 10 }, 0, 1>::decompress_with_buffer(
+            SliceReader::new(&output1),
+            VecWriter::with_capacity(big_test_data.len()),
+            &mut buffer,
+        )
+        .void_unwrap();
+        assert_eq!(output2.as_slice(), big_test_data);
+        assert_ne!(
+            output1,
+            TEST_LZSS
+                .compress(
+                    SliceReader::new(big_test_data),
+                    VecWriter::with_capacity(big_test_data.len()),
+                )
+                .void_unwrap()
+        );
+    }
+
+    #[test]
+    fn test_max_distance() {
+        type RelativeLzss = Lzss<10, 4, 0x20, { 1 << 10 }, { 2 << 10 }, 0, 1>;
+        let big_test_data = include_bytes!("mod.rs");
+        for options in [
+            LzssOptions::RELATIVE,
+            LzssOptions::RELATIVE | LzssOptions::END_MARKER | LzssOptions::EXTENDED_LENGTH,
+            LzssOptions::ADAPTIVE_OFFSET | LzssOptions::END_MARKER,
+        ] {
+            let lzss = TEST_LZSS.with_options(options);
+            // without a limit it's the same as compress
+            assert_eq!(
+                lzss.compress_with_max_distance(
+                    SliceReader::new(big_test_data),
+                    VecWriter::with_capacity(big_test_data.len()),
+                    lzss.n(),
+                )
+                .void_unwrap(),
+                lzss.compress(
+                    SliceReader::new(big_test_data),
+                    VecWriter::with_capacity(big_test_data.len()),
+                )
+                .void_unwrap()
+            );
+            let output1 = lzss
+                .compress_with_max_distance(
+                    SliceReader::new(big_test_data),
+                    VecWriter::with_capacity(big_test_data.len()),
+                    200,
+                )
+                .void_unwrap();
+            // any decompressor can read it
+            let output2 = lzss
+                .decompress(
+                    SliceReader::new(&output1),
+                    VecWriter::with_capacity(big_test_data.len()),
+                )
+                .void_unwrap();
+            assert_eq!(output2.as_slice(), big_test_data);
+            // a window of 256 bytes is enough
+            let mut window = [0u8; 256];
+            let output3 = lzss
+                .decompress_with_window(
+                    SliceReader::new(&output1),
+                    VecWriter::with_capacity(big_test_data.len()),
+                    &mut window,
+                )
+                .void_unwrap();
+            assert_eq!(output3.as_slice(), big_test_data);
+            // but not for larger distances
+            let output4 = lzss
+                .decompress_with_window(
+                    SliceReader::new(
+                        &lzss
+                            .compress_with_max_distance(
+                                SliceReader::new(big_test_data),
+                                VecWriter::with_capacity(big_test_data.len()),
+                                512,
+                            )
+                            .void_unwrap(),
+                    ),
+                    VecWriter::with_capacity(big_test_data.len()),
+                    &mut window,
+                )
+                .void_unwrap();
+            assert_ne!(output4.as_slice(), big_test_data);
+        }
+        // generic
+        let output1 = RelativeLzss::compress_with_max_distance_stack(
+            SliceReader::new(big_test_data),
+            VecWriter::with_capacity(big_test_data.len()),
+            128,
+        )
+        .void_unwrap();
+        assert_eq!(
+            output1,
+            TEST_LZSS
+                .with_options(LzssOptions::RELATIVE)
+                .compress_with_max_distance(
+                    SliceReader::new(big_test_data),
+                    VecWriter::with_capacity(big_test_data.len()),
+                    128,
+                )
+                .void_unwrap()
+        );
+        let mut window = [0u8; 128];
+        let output2 = RelativeLzss::decompress_with_window(
+            SliceReader::new(&output1),
+            VecWriter::with_capacity(big_test_data.len()),
+            &mut window,
+        )
+        .void_unwrap();
+        assert_eq!(output2.as_slice(), big_test_data);
+    }
+
+    #[test]
+    #[should_panic(expected = "is_relative")]
+    fn test_decompress_with_window_absolute() {
+        let mut window = [0u8; 256];
+        let _ = TEST_LZSS.decompress_with_window(
+            SliceReader::new(&COMPRESSED_DATA),
+            VecWriter::with_capacity(TEST_DATA.len()),
+            &mut window,
+        );
+    }
+
+    #[test]
+    fn test_end_marker() {
+        let big_test_data = include_bytes!("mod.rs");
+        for options in [
+            LzssOptions::END_MARKER,
+            LzssOptions::END_MARKER | LzssOptions::RELATIVE,
+        ] {
+            let lzss = TEST_LZSS.with_options(options);
+            let mut compressed = lzss
+                .compress(
+                    SliceReader::new(big_test_data),
+                    VecWriter::with_capacity(big_test_data.len()),
+                )
+                .void_unwrap();
+            // the decompression stops at the marker, the following data is ignored
+            compressed.extend_from_slice(&[0xff; 16]);
+            let output = lzss
+                .decompress(
+                    SliceReader::new(&compressed),
+                    VecWriter::with_capacity(big_test_data.len()),
+                )
+                .void_unwrap();
+            assert_eq!(output.as_slice(), big_test_data);
+        }
+    }
+
+    #[test]
+    fn test_extended_length() {
+        let big_test_data = include_bytes!("mod.rs");
+        let mut input = Vec::from(&big_test_data[..]);
+        input.splice(1000..1000, [0; 5000]);
+        for options in [
+            LzssOptions::EXTENDED_LENGTH,
+            LzssOptions::EXTENDED_LENGTH | LzssOptions::RELATIVE | LzssOptions::END_MARKER,
+        ] {
+            let lzss = TEST_LZSS.with_options(options);
+            let compressed = lzss
+                .compress(
+                    SliceReader::new(&input),
+                    VecWriter::with_capacity(input.len()),
+                )
+                .void_unwrap();
+            let plain = TEST_LZSS
+                .compress(
+                    SliceReader::new(&input),
+                    VecWriter::with_capacity(input.len()),
+                )
+                .void_unwrap();
+            // the zeros are five matches (limited by the buffer) instead of about 300
+            assert!(compressed.len() + 400 < plain.len());
+            let output = lzss
+                .decompress(
+                    SliceReader::new(&compressed),
+                    VecWriter::with_capacity(input.len()),
+                )
+                .void_unwrap();
+            assert_eq!(output, input);
+        }
+        // the generic version
+        let mut buffer = [0; 1 << 10];
+        let output = Lzss::<10, 4, 0x20, { 1 << 10 }, { 2 << 10 }, 0, 4>::decompress_with_buffer(
+            SliceReader::new(
+                &TEST_LZSS
+                    .with_options(LzssOptions::EXTENDED_LENGTH)
+                    .compress(
+                        SliceReader::new(&input),
+                        VecWriter::with_capacity(input.len()),
+                    )
+                    .void_unwrap(),
+            ),
+            VecWriter::with_capacity(input.len()),
+            &mut buffer,
+        )
+        .void_unwrap();
+        assert_eq!(output, input);
+    }
+
+    #[test]
+    fn test_adaptive_offset() {
+        let small_input = b"abcabcabc, hello hello hello";
+        let compressed = TEST_LZSS
+            .with_options(LzssOptions::ADAPTIVE_OFFSET)
+            .compress(SliceReader::new(small_input), VecWriter::with_capacity(30))
+            .void_unwrap();
+        let relative = TEST_LZSS
+            .with_options(LzssOptions::RELATIVE)
+            .compress(SliceReader::new(small_input), VecWriter::with_capacity(30))
+            .void_unwrap();
+        assert!(compressed.len() < relative.len());
+
+        let big_test_data = include_bytes!("mod.rs");
+        for options in [
+            LzssOptions::ADAPTIVE_OFFSET,
+            LzssOptions::ADAPTIVE_OFFSET | LzssOptions::RELATIVE,
+            LzssOptions::ADAPTIVE_OFFSET | LzssOptions::END_MARKER,
+            LzssOptions::ADAPTIVE_OFFSET | LzssOptions::EXTENDED_LENGTH,
+        ] {
+            for lzss in [
+                TEST_LZSS.with_options(options),
+                LzssDyn::new(8, 1, 0).unwrap().with_options(options),
+                LzssDyn::new(12, 6, 0).unwrap().with_options(options),
+            ] {
+                for len in (0..40).chain([big_test_data.len()]) {
+                    let input = &big_test_data[..len];
+                    let compressed = lzss
+                        .compress(SliceReader::new(input), VecWriter::with_capacity(len))
+                        .void_unwrap();
+                    let output = lzss
+                        .decompress(SliceReader::new(&compressed), VecWriter::with_capacity(len))
+                        .void_unwrap();
+                    assert_eq!(output, input);
+                }
+            }
+        }
+        // the generic version
+        let mut buffer = [0; 1 << 10];
+        let output = Lzss::<10, 4, 0x20, { 1 << 10 }, { 2 << 10 }, 0, 8>::decompress_with_buffer(
+            SliceReader::new(&compressed),
+            VecWriter::with_capacity(30),
+            &mut buffer,
+        )
+        .void_unwrap();
+        assert_eq!(output, small_input);
+    }
+
+    #[test]
+    fn test_options_all_functions() {
+        let big_test_data = include_bytes!("mod.rs");
+        // some hardly compressible data first, so that compress_in_place can fail partway
+        let mut input = Vec::with_capacity(10000);
+        let mut x: u32 = 1;
+        for _ in 0..1000 {
+            x = x.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            input.push((x >> 16) as u8);
+        }
+        input.extend_from_slice(&big_test_data[..3000]);
+        input.splice(2000..2000, [0; 2000]);
+        for bits in 0..16 {
+            let options = LzssOptions::from_bits(bits).unwrap();
+            for lzss in [
+                TEST_LZSS.with_options(options),
+                LzssDyn::new_with_p(8, 3, 0, 2)
+                    .unwrap()
+                    .with_options(options),
+            ] {
+                let compressed = lzss
+                    .compress(
+                        SliceReader::new(&input),
+                        VecWriter::with_capacity(input.len()),
+                    )
+                    .void_unwrap();
+                // all compressors create the same output
+                assert_eq!(
+                    lzss.compress_ring(
+                        SliceReader::new(&input),
+                        VecWriter::with_capacity(input.len()),
+                    )
+                    .void_unwrap(),
+                    compressed,
+                    "compress_ring {options:?}"
+                );
+                assert_eq!(
+                    lzss.compress_slice(&input, VecWriter::with_capacity(input.len()))
+                        .void_unwrap(),
+                    compressed,
+                    "compress_slice {options:?}"
+                );
+                let offset = lzss.min_offset() + input.len() / 8;
+                let mut io = vec![0; offset + input.len()];
+                io[offset..].copy_from_slice(&input);
+                let (c, u) = lzss.compress_in_place(&mut io, offset);
+                assert_eq!(u, None);
+                assert_eq!(&io[..c], compressed, "compress_in_place {options:?}");
+
+                // all decompressors create the input
+                assert_eq!(
+                    lzss.decompress(
+                        SliceReader::new(&compressed),
+                        VecWriter::with_capacity(input.len()),
+                    )
+                    .void_unwrap(),
+                    input
+                );
+                assert_eq!(
+                    lzss.decompress_to_vec(SliceReader::new(&compressed))
+                        .void_unwrap(),
+                    input
+                );
+                let mut output = vec![0; input.len()];
+                assert_eq!(
+                    lzss.decompress_to_slice(SliceReader::new(&compressed), &mut output)
+                        .void_read_unwrap(),
+                    Ok(input.len())
+                );
+                assert_eq!(output, input);
+                let mut io = vec![0; input.len() + lzss.decompress_in_place_margin(input.len())];
+                let offset = io.len() - compressed.len();
+                io[offset..].copy_from_slice(&compressed);
+                assert_eq!(
+                    lzss.decompress_in_place(&mut io, offset, compressed.len()),
+                    Ok(input.len())
+                );
+                assert_eq!(&io[..input.len()], input);
+
+                // a compress_in_place which fails partway
+                let offset = lzss.min_offset() + 10;
+                let mut io = vec![0; offset + input.len()];
+                io[offset..].copy_from_slice(&input);
+                let (c, u) = lzss.compress_in_place(&mut io, offset);
+                let u = u.unwrap();
+                assert!(c > 0);
+                let mut combined = io[..c - 1].to_vec();
+                combined.extend(
+                    lzss.compress_in_place_continue(
+                        &io,
+                        c,
+                        u,
+                        VecWriter::with_capacity(input.len()),
+                    )
+                    .void_unwrap(),
+                );
+                assert_eq!(
+                    lzss.decompress_to_vec(SliceReader::new(&combined))
+                        .void_unwrap(),
+                    input,
+                    "compress_in_place_continue {options:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_decompress_to_slice() {
+        let mut output = [0u8; 30];
+        let size = TEST_LZSS
+            .decompress_to_slice(SliceReader::new(&COMPRESSED_DATA), &mut output)
+            .void_read_unwrap();
+        assert_eq!(size, Ok(TEST_DATA.len()));
+        assert_eq!(&output[..TEST_DATA.len()], TEST_DATA);
+        let size = TEST_LZSS
+            .decompress_to_slice(SliceReader::new(&COMPRESSED_DATA), &mut output[..26])
+            .void_read_unwrap();
+        assert_eq!(size, Err(SliceWriteError));
+    }
+
+    #[test]
+    fn test_decompress_to_vec() {
+        let big_test_data = include_bytes!("mod.rs");
+        let compressed = TEST_LZSS
+            .compress(
+                SliceReader::new(big_test_data),
+                VecWriter::with_capacity(big_test_data.len()),
+            )
+            .void_unwrap();
+        let output = TEST_LZSS
+            .decompress_to_vec(SliceReader::new(&compressed))
+            .void_unwrap();
+        assert_eq!(output.as_slice(), big_test_data);
+    }
+
+    #[test]
+    fn test_decompress_in_place() {
+        let big_test_data = include_bytes!("mod.rs");
+        let compressed = TEST_LZSS
+            .compress(
+                SliceReader::new(big_test_data),
+                VecWriter::with_capacity(big_test_data.len()),
+            )
+            .void_unwrap();
+        let mut io = vec![
+            0u8;
+            big_test_data.len()
+                + TEST_LZSS.decompress_in_place_margin(big_test_data.len())
+        ];
+        let offset = io.len() - compressed.len();
+        io[offset..].copy_from_slice(&compressed);
+        assert_eq!(
+            TEST_LZSS.decompress_in_place(&mut io, offset, compressed.len()),
+            Ok(big_test_data.len())
+        );
+        assert_eq!(&io[..big_test_data.len()], big_test_data);
+    }
+
+    #[test]
+    fn test_compress_in_place() {
+        let big_test_data = include_bytes!("mod.rs");
+        let compressed = TEST_LZSS
+            .compress(
+                SliceReader::new(big_test_data),
+                VecWriter::with_capacity(big_test_data.len()),
+            )
+            .void_unwrap();
+        let offset = TEST_LZSS.min_offset() + big_test_data.len() / 8;
+        let mut io = vec![0; offset + big_test_data.len()];
+        io[offset..].copy_from_slice(big_test_data);
+        let (c, u) = TEST_LZSS.compress_in_place(&mut io, offset);
+        assert_eq!(u, None);
+        assert_eq!(&io[0..c], compressed.as_slice());
+    }
 }
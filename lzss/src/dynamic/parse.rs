@@ -0,0 +1,93 @@
+use crate::dynamic::{LzssDyn, LzssDynError};
+use crate::options::LzssOptions;
+use core::str::FromStr;
+
+fn parse_dec_or_hex_u8(i: &str) -> Option<u8> {
+    if let Some(i) = i.strip_prefix("0x") {
+        u8::from_str_radix(i, 16).ok()
+    } else {
+        u8::from_str(i).ok()
+    }
+}
+
+fn parse_option(i: &str) -> Result<LzssOptions, LzssDynError> {
+    LzssOptions::NAMES
+        .iter()
+        .find(|(_, name)| *name == i)
+        .map(|(option, _)| *option)
+        .ok_or_else(|| {
+            if i.parse::<usize>().is_ok() {
+                LzssDynError::ParseSyntax
+            } else {
+                LzssDynError::UnknownOptions
+            }
+        })
+}
+
+/// Parse the parameters from the `ei,ej,c` or `ei,ej,c,p` syntax, e.g. `10,4,0x20`.
+///
+/// The `c` may be decimal or hexadecimal (with `0x` prefix), whitespace around the
+/// numbers is ignored. The parameters are checked with [`LzssDyn::new`] or
+/// [`LzssDyn::new_with_p`].
+///
+/// The [`LzssOptions`] may follow by name, e.g. `10,4,0x20,relative`.
+///
+/// ```rust
+/// # use lzss::{LzssDyn, LzssDynError, LzssOptions};
+/// let my_lzss: LzssDyn = "10,4,0x20".parse()?;
+/// assert_eq!(my_lzss, LzssDyn::new(10, 4, 0x20)?);
+/// assert_eq!(my_lzss.to_string(), "10,4,0x20");
+/// let my_lzss: LzssDyn = "12,4,0x20,2".parse()?;
+/// assert_eq!(my_lzss, LzssDyn::new_with_p(12, 4, 0x20, 2)?);
+/// assert_eq!(my_lzss.to_string(), "12,4,0x20,2");
+/// let my_lzss: LzssDyn = "10,4,0x20,relative".parse()?;
+/// assert_eq!(my_lzss, LzssDyn::new(10, 4, 0x20)?.with_options(LzssOptions::RELATIVE));
+/// assert_eq!(my_lzss.to_string(), "10,4,0x20,relative");
+/// # Ok::<(), LzssDynError>(())
+/// ```
+impl FromStr for LzssDyn {
+    type Err = LzssDynError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut params = s.split(',').map(str::trim).peekable();
+        let (ei, ej, c) = match (params.next(), params.next(), params.next()) {
+            (Some(ei), Some(ej), Some(c)) => (ei, ej, c),
+            _ => return Err(LzssDynError::ParseSyntax),
+        };
+        let p = params.next_if(|p| LzssOptions::NAMES.iter().all(|(_, name)| name != p));
+        let mut options = LzssOptions::NONE;
+        for option in params {
+            options = options | parse_option(option)?;
+        }
+        let ei = ei.parse::<usize>().map_err(|_| LzssDynError::ParseEi)?;
+        let ej = ej.parse::<usize>().map_err(|_| LzssDynError::ParseEj)?;
+        let c = parse_dec_or_hex_u8(c).ok_or(LzssDynError::ParseC)?;
+        match p {
+            None => LzssDyn::new(ei, ej, c),
+            Some(p) => {
+                let p = p.parse::<usize>().map_err(|_| LzssDynError::ParseP)?;
+                LzssDyn::new_with_p(ei, ej, c, p)
+            }
+        }
+        .map(|lzss| lzss.with_options(options))
+    }
+}
+
+/// Print the parameters in the `ei,ej,c` syntax, e.g. `10,4,0x20`.
+///
+/// The `p` is only appended (`ei,ej,c,p`) when it's not `(1 + ei + ej) / 9`,
+/// followed by the names of the [`LzssOptions`].
+impl core::fmt::Display for LzssDyn {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{},{},0x{:02x}", self.ei, self.ej, self.c)?;
+        if self.p != (1 + self.ei + self.ej) / 9 {
+            write!(f, ",{}", self.p)?;
+        }
+        for (option, name) in LzssOptions::NAMES {
+            if self.options.contains(option) {
+                write!(f, ",{name}")?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,360 @@
+use crate::bits::{BitReader, BitWriter};
+use crate::dynamic::LzssDyn;
+use crate::error::LzssError;
+use crate::macros::{get, search_loop, set};
+use crate::read_write::{Read, Write};
+use core::fmt::Display;
+
+// The word format is the normal format with 16-bit symbols:
+// * a literal is `1` and the 16-bit word
+// * a match is `0`, `ei` bits of position and `ej` bits of length, both counted in words
+// The words are read and written as little-endian byte pairs, the buffer is filled with `C` in
+// both bytes.
+
+impl LzssDyn {
+    /// Compress the input data into the output, with 16-bit words as symbols.
+    ///
+    /// The input is read as little-endian words, thus it must have an even length.
+    /// Buffer and matches count in words, i.e. matches are up to `(1 << EJ) + P` words long and the
+    /// buffer has `1 << EI` words. This is useful for data which repeats at 16-bit
+    /// granularity, like tilemaps, audio samples or UTF-16 text.
+    ///
+    /// The threshold `P` is the same as for bytes, it's part of the format (the length field
+    /// starts at `P + 1`). A literal takes 17 bits and a match at most 25 bits, thus a match of
+    /// two words is always shorter and `p = 1` compresses best (see [`LzssDyn::new_with_p`]),
+    /// the default `P` is chosen for bytes.
+    ///
+    /// The output is a separate format, it can only be decompressed with
+    /// [`LzssDyn::decompress_words`] (or [`LzssDyn::decompress_words_with_buffer`]).
+    ///
+    /// The buffer, with `2 * (1 << EI)` words, is allocated on the heap.
+    /// It will be asserted at runtime that there are no options.
+    ///
+    /// ```rust
+    /// # use lzss::{LzssDyn, LzssDynError, SliceReader, VecWriter};
+    /// let my_lzss = LzssDyn::new(10, 4, 0x00)?;
+    /// let input = "Example Example".encode_utf16().flat_map(u16::to_le_bytes).collect::<Vec<u8>>();
+    /// let compressed = my_lzss
+    ///     .compress_words(SliceReader::new(&input), VecWriter::with_capacity(30))
+    ///     .unwrap();
+    /// let output = my_lzss
+    ///     .decompress_words(SliceReader::new(&compressed), VecWriter::with_capacity(30))
+    ///     .unwrap();
+    /// assert_eq!(output, input);
+    /// # Ok::<(), LzssDynError>(())
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(any(feature = "alloc", feature = "std"))))]
+    #[cfg(feature = "alloc")]
+    pub fn compress_words<R: Read, W: Write>(
+        &self,
+        mut reader: R,
+        mut writer: W,
+    ) -> Result<W::Output, LzssWordError<R::Error, W::Error>> {
+        let mut buffer = vec![self.c_word(); 2 * self.n()];
+        self.compress_words_internal(&mut reader, &mut writer, &mut buffer)?;
+        Ok(writer.finish().map_err(LzssError::WriteError)?)
+    }
+
+    /// Compress the input data into the output, with 16-bit words as symbols
+    /// (see [`LzssDyn::compress_words`]).
+    ///
+    /// It will be asserted at runtime that the buffer is at least `2 * (1 << EI)` words.
+    pub fn compress_words_with_buffer<R: Read, W: Write>(
+        &self,
+        mut reader: R,
+        mut writer: W,
+        buffer: &mut [u16],
+    ) -> Result<W::Output, LzssWordError<R::Error, W::Error>> {
+        assert!(buffer.len() >= 2 * self.n());
+        buffer[..self.n() - self.f()].fill(self.c_word());
+        self.compress_words_internal(&mut reader, &mut writer, buffer)?;
+        Ok(writer.finish().map_err(LzssError::WriteError)?)
+    }
+
+    /// Decompress the input data into the output, with 16-bit words as symbols
+    /// (see [`LzssDyn::compress_words`]).
+    ///
+    /// The buffer, with `1 << EI` words, is allocated on the heap.
+    #[cfg_attr(docsrs, doc(cfg(any(feature = "alloc", feature = "std"))))]
+    #[cfg(feature = "alloc")]
+    pub fn decompress_words<R: Read, W: Write>(
+        &self,
+        mut reader: R,
+        mut writer: W,
+    ) -> Result<W::Output, LzssError<R::Error, W::Error>> {
+        let mut buffer = vec![self.c_word(); self.n()];
+        self.decompress_words_internal(&mut reader, &mut writer, &mut buffer)?;
+        writer.finish().map_err(LzssError::WriteError)
+    }
+
+    /// Decompress the input data into the output, with 16-bit words as symbols
+    /// (see [`LzssDyn::compress_words`]).
+    ///
+    /// It will be asserted at runtime that the buffer is at least `1 << EI` words.
+    pub fn decompress_words_with_buffer<R: Read, W: Write>(
+        &self,
+        mut reader: R,
+        mut writer: W,
+        buffer: &mut [u16],
+    ) -> Result<W::Output, LzssError<R::Error, W::Error>> {
+        assert!(buffer.len() >= self.n());
+        buffer[..self.n()].fill(self.c_word());
+        self.decompress_words_internal(&mut reader, &mut writer, buffer)?;
+        writer.finish().map_err(LzssError::WriteError)
+    }
+
+    #[inline(always)]
+    const fn c_word(&self) -> u16 {
+        u16::from_le_bytes([self.c, self.c])
+    }
+
+    #[inline(always)]
+    fn read_word<R: Read, W>(reader: &mut R) -> Result<Option<u16>, LzssWordError<R::Error, W>> {
+        match reader.read().map_err(LzssError::ReadError)? {
+            None => Ok(None),
+            Some(low) => match reader.read().map_err(LzssError::ReadError)? {
+                None => Err(LzssWordError::OddLength),
+                Some(high) => Ok(Some(u16::from_le_bytes([low, high]))),
+            },
+        }
+    }
+
+    #[inline(always)]
+    fn write_word<W: Write>(writer: &mut W, data: u16) -> Result<(), W::Error> {
+        for b in data.to_le_bytes() {
+            writer.write(b)?;
+        }
+        Ok(())
+    }
+
+    // This is the same as compress_window_internal, only with words (and without options).
+
+    // Allow many single char names, they are the ones of compress_window_internal.
+    #[allow(clippy::many_single_char_names)]
+    fn compress_words_internal<R: Read, W: Write>(
+        &self,
+        reader: &mut R,
+        writer: &mut W,
+        buffer: &mut [u16],
+    ) -> Result<(), LzssWordError<R::Error, W::Error>> {
+        assert!(self.options.is_empty(), "options are not supported");
+        let mut bit_writer = BitWriter::new(writer);
+
+        let mut buffer_end = self.n() - self.f();
+        while buffer_end < 2 * self.n() {
+            match Self::read_word(reader)? {
+                None => break,
+                Some(data) => {
+                    set!(buffer, buffer_end, data);
+                    buffer_end += 1;
+                }
+            }
+        }
+
+        let mut r = self.n() - self.f();
+        let mut s = 0;
+        while r < buffer_end {
+            let f1 = self.f().min(buffer_end - r);
+            let c = get!(buffer, r);
+            let (x, mut y) = Self::find_word_match(buffer, s, r, f1);
+            // P is also the offset of the length field, thus shorter matches can't be written
+            // (see compress_words for why it's not derived from the 17 bit literals)
+            if y <= self.p() {
+                bit_writer
+                    .write_bits(0x1_0000 | u32::from(c), 17)
+                    .map_err(LzssError::WriteError)?;
+                y = 1;
+            } else {
+                bit_writer
+                    .write_bits(
+                        (((x & (self.n() - 1)) as u32) << self.ej) | ((y - (self.p() + 1)) as u32),
+                        1 + self.ei + self.ej,
+                    )
+                    .map_err(LzssError::WriteError)?;
+            }
+            r += y;
+            s += y;
+            if r >= self.n() * 2 - self.f() {
+                buffer.copy_within(self.n()..2 * self.n(), 0);
+                buffer_end -= self.n();
+                r -= self.n();
+                s -= self.n();
+                while buffer_end < 2 * self.n() {
+                    match Self::read_word(reader)? {
+                        None => break,
+                        Some(data) => {
+                            set!(buffer, buffer_end, data);
+                            buffer_end += 1;
+                        }
+                    }
+                }
+            }
+        }
+        Ok(bit_writer.flush().map_err(LzssError::WriteError)?)
+    }
+
+    // The same as find_match, only with words.
+    #[allow(clippy::many_single_char_names)]
+    #[inline(always)]
+    fn find_word_match(buffer: &[u16], s: usize, r: usize, f1: usize) -> (usize, usize) {
+        let mut x = 0;
+        let mut y = 1;
+        let c = get!(buffer, r);
+        for (i, &ci) in search_loop!(s, r, buffer) {
+            if ci == c {
+                let mut j = 1;
+                while j < f1 {
+                    if get!(buffer, i + j) != get!(buffer, r + j) {
+                        break;
+                    }
+                    j += 1;
+                }
+                if j > y {
+                    x = i;
+                    y = j;
+                }
+            }
+        }
+        (x, y)
+    }
+
+    fn decompress_words_internal<R: Read, W: Write>(
+        &self,
+        reader: &mut R,
+        writer: &mut W,
+        buffer: &mut [u16],
+    ) -> Result<(), LzssError<R::Error, W::Error>> {
+        assert!(self.options.is_empty(), "options are not supported");
+        let mut bit_reader = BitReader::new(reader);
+
+        let mut r = self.n() - self.f();
+        loop {
+            let flag = match bit_reader.read_bits(1).map_err(LzssError::ReadError)? {
+                None => return Ok(()),
+                Some(flag) => flag,
+            };
+            let len = if flag != 0 { 16 } else { self.ei + self.ej };
+            let inp = match bit_reader.read_bits(len).map_err(LzssError::ReadError)? {
+                None => return Ok(()),
+                Some(inp) => inp,
+            };
+            if flag != 0 {
+                Self::write_word(writer, inp as u16).map_err(LzssError::WriteError)?;
+                set!(buffer, r, inp as u16);
+                r = (r + 1) & (self.n() - 1);
+            } else {
+                let i = (inp >> self.ej) as usize;
+                let j = (inp & ((1 << self.ej) - 1)) as usize;
+                for k in 0..=j + self.p() {
+                    let b = get!(buffer, (i + k) & (self.n() - 1));
+                    Self::write_word(writer, b).map_err(LzssError::WriteError)?;
+                    set!(buffer, r, b);
+                    r = (r + 1) & (self.n() - 1);
+                }
+            }
+        }
+    }
+}
+
+/// The error returned by [`LzssDyn::compress_words`].
+#[derive(Debug, Eq, PartialEq)]
+pub enum LzssWordError<R, W> {
+    /// Contains the read or write error.
+    Lzss(LzssError<R, W>),
+    /// The input ended after an odd number of bytes.
+    OddLength,
+}
+
+impl<R, W> From<LzssError<R, W>> for LzssWordError<R, W> {
+    #[inline]
+    fn from(error: LzssError<R, W>) -> Self {
+        LzssWordError::Lzss(error)
+    }
+}
+
+impl<R: Display, W: Display> core::fmt::Display for LzssWordError<R, W> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            LzssWordError::Lzss(error) => error.fmt(f),
+            LzssWordError::OddLength => f.write_str("The input has an odd length"),
+        }
+    }
+}
+
+/// Implementation of [`Error`](std::error::Error) for [`LzssWordError`]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[cfg(feature = "std")]
+impl<R, W> std::error::Error for LzssWordError<R, W>
+where
+    R: std::error::Error + 'static,
+    W: std::error::Error + 'static,
+{
+    #[inline]
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LzssWordError::Lzss(error) => error.source(),
+            LzssWordError::OddLength => None,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use crate::dynamic::{LzssDyn, LzssWordError};
+    use crate::slice::SliceReader;
+    use crate::vec::VecWriter;
+    use crate::void::ResultLzssErrorVoidExt;
+    #[cfg(not(feature = "std"))]
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_words() {
+        let lzss = LzssDyn::new(10, 4, 0x20).unwrap();
+        // an odd number of bytes in front, thus the byte matches are misaligned
+        let big_test_data = include_bytes!("word.rs");
+        let mut input = Vec::from(&b"odd"[..]);
+        for &b in &big_test_data[..big_test_data.len() & !1] {
+            input.extend_from_slice(&[b, 0]);
+        }
+        input.push(0);
+        let compressed = lzss
+            .compress_words(
+                SliceReader::new(&input),
+                VecWriter::with_capacity(input.len()),
+            )
+            .unwrap();
+        let bytes = lzss
+            .compress(
+                SliceReader::new(&input),
+                VecWriter::with_capacity(input.len()),
+            )
+            .void_unwrap();
+        assert!(compressed.len() < bytes.len());
+        let mut buffer = [0; 1 << 10];
+        let output = lzss
+            .decompress_words_with_buffer(
+                SliceReader::new(&compressed),
+                VecWriter::with_capacity(input.len()),
+                &mut buffer,
+            )
+            .unwrap();
+        assert_eq!(output, input);
+        // with a buffer on the stack
+        let mut buffer = [0; 2 << 10];
+        assert_eq!(
+            lzss.compress_words_with_buffer(
+                SliceReader::new(&input),
+                VecWriter::with_capacity(input.len()),
+                &mut buffer,
+            ),
+            Ok(compressed)
+        );
+        assert_eq!(
+            lzss.compress_words(
+                SliceReader::new(&input[1..]),
+                VecWriter::with_capacity(input.len()),
+            ),
+            Err(LzssWordError::OddLength)
+        );
+    }
+}
@@ -0,0 +1,321 @@
+/* This file is based on the LZSS encoder-decoder  (c) Haruhiko Okumura */
+
+use crate::error::LzssError;
+use crate::generic::Lzss;
+use crate::macros::{get, set};
+use crate::read_write::{Read, Write};
+#[cfg(all(feature = "safe", feature = "alloc", not(feature = "std")))]
+use core::convert::TryInto;
+
+const EI: usize = 12;
+const EJ: usize = 4;
+pub(crate) const N: usize = 1 << EI;
+pub(crate) const N2: usize = 2 * N;
+const P: usize = 2; /* If match length <= P then output one character */
+const F: usize = (1 << EJ) + P; /* lookahead buffer size */
+
+/// The classic flag-byte format of `LZSS.C` (by Haruhiko Okumura, 1989).
+///
+/// Instead of a flag bit in front of every token, eight flags are grouped into a byte
+/// (lsb first, `1` is a literal) which is followed by the eight byte-aligned tokens.
+/// A literal is one byte, a match is two bytes with a 12-bit position and a 4-bit length:
+/// `position & 0xff`, `((position >> 4) & 0xf0) | (length - 3)`.
+///
+/// The parameters are fixed, they are equal to `Lzss<12, 4, C, 4096, 8192, 2>`,
+/// i.e. a buffer of 4096 bytes, matches are 3 to 18 bytes long.
+///
+/// # Parameters
+/// * `C` - The initial fill byte of the buffer, `0x20` (space) in the original
+///
+/// # Example
+/// ```rust
+/// # use lzss::{LzssFlagByte, ResultLzssErrorVoidExt, SliceReader, VecWriter};
+/// type MyLzss = LzssFlagByte<0x20>;
+/// let input = b"abcabcabc";
+/// let result = MyLzss::compress_stack(
+///   SliceReader::new(input),
+///   VecWriter::with_capacity(30),
+/// );
+/// assert_eq!(result.void_unwrap(), [0x07, b'a', b'b', b'c', 0xee, 0xf3]);
+/// ```
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct LzssFlagByte<const C: u8>(());
+
+impl<const C: u8> LzssFlagByte<C> {
+    /// The size of the buffer for decompression, `1 << 12` (`N` of the equal [`Lzss`]).
+    pub const N: usize = N;
+
+    /// The size of the buffer for compression, `2 << 12` (`N2` of the equal [`Lzss`]).
+    pub const N2: usize = N2;
+
+    /// Create a value of this zero-sized type.
+    ///
+    /// This is only required for using it through the [`LzssCodec`](crate::LzssCodec) trait.
+    #[must_use]
+    pub const fn new() -> Self {
+        LzssFlagByte(())
+    }
+
+    /// Compress the input data into the output.
+    ///
+    /// The buffer, with [`LzssFlagByte::N2`] bytes, is allocated on the stack.
+    pub fn compress_stack<R: Read, W: Write>(
+        mut reader: R,
+        mut writer: W,
+    ) -> Result<W::Output, LzssError<R::Error, W::Error>> {
+        let mut buffer = [C; N2];
+        Self::compress_internal(&mut reader, &mut writer, &mut buffer)?;
+        writer.finish().map_err(LzssError::WriteError)
+    }
+
+    /// Compress the input data into the output.
+    ///
+    /// The buffer, with [`LzssFlagByte::N2`] bytes, is allocated on the heap.
+    #[cfg_attr(docsrs, doc(cfg(any(feature = "alloc", feature = "std"))))]
+    #[cfg(feature = "alloc")]
+    pub fn compress_heap<R: Read, W: Write>(
+        mut reader: R,
+        mut writer: W,
+    ) -> Result<W::Output, LzssError<R::Error, W::Error>> {
+        let mut buffer = vec![C; N2];
+        #[cfg(not(feature = "safe"))]
+        let buffer = unsafe { &mut *(buffer.as_mut_ptr().cast::<[u8; N2]>()) };
+        #[cfg(feature = "safe")]
+        let buffer: &mut [u8; N2] = (&mut buffer[..]).try_into().unwrap();
+        Self::compress_internal(&mut reader, &mut writer, buffer)?;
+        writer.finish().map_err(LzssError::WriteError)
+    }
+
+    /// Compress the input data into the output.
+    pub fn compress_with_buffer<R: Read, W: Write>(
+        mut reader: R,
+        mut writer: W,
+        buffer: &mut [u8; N2],
+    ) -> Result<W::Output, LzssError<R::Error, W::Error>> {
+        buffer[..N - F].fill(C);
+        Self::compress_internal(&mut reader, &mut writer, buffer)?;
+        writer.finish().map_err(LzssError::WriteError)
+    }
+
+    /// Decompress the input data into the output.
+    ///
+    /// The buffer, with [`LzssFlagByte::N`] bytes, is allocated on the stack.
+    pub fn decompress_stack<R: Read, W: Write>(
+        mut reader: R,
+        mut writer: W,
+    ) -> Result<W::Output, LzssError<R::Error, W::Error>> {
+        let mut buffer = [C; N];
+        Self::decompress_internal(&mut reader, &mut writer, &mut buffer)?;
+        writer.finish().map_err(LzssError::WriteError)
+    }
+
+    /// Decompress the input data into the output.
+    ///
+    /// The buffer, with [`LzssFlagByte::N`] bytes, is allocated on the heap.
+    #[cfg_attr(docsrs, doc(cfg(any(feature = "alloc", feature = "std"))))]
+    #[cfg(feature = "alloc")]
+    pub fn decompress_heap<R: Read, W: Write>(
+        mut reader: R,
+        mut writer: W,
+    ) -> Result<W::Output, LzssError<R::Error, W::Error>> {
+        let mut buffer = vec![C; N];
+        #[cfg(not(feature = "safe"))]
+        let buffer = unsafe { &mut *(buffer.as_mut_ptr().cast::<[u8; N]>()) };
+        #[cfg(feature = "safe")]
+        let buffer: &mut [u8; N] = (&mut buffer[..]).try_into().unwrap();
+        Self::decompress_internal(&mut reader, &mut writer, buffer)?;
+        writer.finish().map_err(LzssError::WriteError)
+    }
+
+    /// Decompress the input data into the output.
+    pub fn decompress_with_buffer<R: Read, W: Write>(
+        mut reader: R,
+        mut writer: W,
+        buffer: &mut [u8; N],
+    ) -> Result<W::Output, LzssError<R::Error, W::Error>> {
+        buffer.fill(C);
+        Self::decompress_internal(&mut reader, &mut writer, buffer)?;
+        writer.finish().map_err(LzssError::WriteError)
+    }
+
+    // The buffer handling and the search are the same as for Lzss, only the output differs.
+
+    // Allow many single char names, this is done to copy the original code as close as possible.
+    #[allow(clippy::many_single_char_names)]
+    #[inline(always)]
+    pub(crate) fn compress_internal<R: Read, W: Write>(
+        reader: &mut R,
+        writer: &mut W,
+        buffer: &mut [u8; N2],
+    ) -> Result<(), LzssError<R::Error, W::Error>> {
+        // code_buf[0] contains the flags, followed by up to eight tokens (at most two bytes each)
+        let mut code_buf = [0u8; 17];
+        let mut code_len = 1;
+        let mut mask = 1u8;
+
+        let mut buffer_end = N - F;
+        while buffer_end < N2 {
+            match reader.read().map_err(LzssError::ReadError)? {
+                None => break,
+                Some(data) => {
+                    set!(buffer, buffer_end, data);
+                    buffer_end += 1;
+                }
+            }
+        }
+
+        let mut r = N - F;
+        let mut s = 0;
+        while r < buffer_end {
+            let f1 = F.min(buffer_end - r);
+            let c = get!(buffer, r);
+            let (x, mut y) = Lzss::<EI, EJ, C, N, N2, P>::find_match(buffer, s, r, f1);
+            if y <= P {
+                code_buf[0] |= mask;
+                code_buf[code_len] = c;
+                code_len += 1;
+                y = 1;
+            } else {
+                let x = x & (N - 1);
+                code_buf[code_len] = x as u8;
+                code_buf[code_len + 1] = (((x >> 4) & 0xf0) | (y - (P + 1))) as u8;
+                code_len += 2;
+            }
+            mask <<= 1;
+            if mask == 0 {
+                for &b in &code_buf[..code_len] {
+                    writer.write(b).map_err(LzssError::WriteError)?;
+                }
+                code_buf[0] = 0;
+                code_len = 1;
+                mask = 1;
+            }
+            r += y;
+            s += y;
+            if r >= N2 - F {
+                buffer.copy_within(N..N2, 0);
+                buffer_end -= N;
+                r -= N;
+                s -= N;
+                while buffer_end < N2 {
+                    match reader.read().map_err(LzssError::ReadError)? {
+                        None => break,
+                        Some(data) => {
+                            set!(buffer, buffer_end, data);
+                            buffer_end += 1;
+                        }
+                    }
+                }
+            }
+        }
+        if code_len > 1 {
+            for &b in &code_buf[..code_len] {
+                writer.write(b).map_err(LzssError::WriteError)?;
+            }
+        }
+        Ok(())
+    }
+
+    // Allow many single char names, this is done to copy the original code as close as possible.
+    #[allow(clippy::many_single_char_names)]
+    #[inline(always)]
+    pub(crate) fn decompress_internal<R: Read, W: Write>(
+        reader: &mut R,
+        writer: &mut W,
+        buffer: &mut [u8; N],
+    ) -> Result<(), LzssError<R::Error, W::Error>> {
+        let mut r = N - F;
+        let mut flags = 0u16;
+        loop {
+            flags >>= 1;
+            if (flags & 0x100) == 0 {
+                match reader.read().map_err(LzssError::ReadError)? {
+                    None => return Ok(()),
+                    Some(c) => flags = u16::from(c) | 0xff00, /* uses higher byte cleverly to count eight */
+                }
+            }
+            if (flags & 1) != 0 {
+                match reader.read().map_err(LzssError::ReadError)? {
+                    None => return Ok(()),
+                    Some(c) => {
+                        writer.write(c).map_err(LzssError::WriteError)?;
+                        set!(buffer, r, c);
+                        r = (r + 1) & (N - 1);
+                    }
+                }
+            } else {
+                let (i, j) = match (
+                    reader.read().map_err(LzssError::ReadError)?,
+                    reader.read().map_err(LzssError::ReadError)?,
+                ) {
+                    (Some(i), Some(j)) => (i as usize, j as usize),
+                    _ => return Ok(()),
+                };
+                let i = i | ((j & 0xf0) << 4);
+                let j = j & 0x0f;
+                for k in 0..=j + P {
+                    let b = get!(buffer, (i + k) & (N - 1));
+                    writer.write(b).map_err(LzssError::WriteError)?;
+                    set!(buffer, r, b);
+                    r = (r + 1) & (N - 1);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use crate::flag_byte::LzssFlagByte;
+    use crate::slice::SliceReader;
+    use crate::vec::VecWriter;
+    use crate::void::ResultLzssErrorVoidExt;
+
+    type TestLZSS = LzssFlagByte<0x20>;
+
+    const TEST_DATA: &[u8; 13] = b"  abcabcabc  ";
+    const COMPRESSED_DATA: [u8; 10] = [0xdf, b' ', b' ', b'a', b'b', b'c', 0xf0, 0xf3, b' ', b' '];
+
+    #[test]
+    fn test_compress() {
+        let output = TestLZSS::compress_stack(
+            SliceReader::new(TEST_DATA),
+            VecWriter::with_capacity(COMPRESSED_DATA.len()),
+        )
+        .void_unwrap();
+        assert_eq!(output.as_slice(), COMPRESSED_DATA);
+    }
+
+    #[test]
+    fn test_decompress() {
+        let output = TestLZSS::decompress_stack(
+            SliceReader::new(&COMPRESSED_DATA),
+            VecWriter::with_capacity(TEST_DATA.len()),
+        )
+        .void_unwrap();
+        assert_eq!(output.as_slice(), TEST_DATA);
+    }
+
+    #[test]
+    fn test_compress_big() {
+        let big_test_data = include_bytes!("flag_byte.rs");
+        let output1 = TestLZSS::compress_heap(
+            SliceReader::new(big_test_data),
+            VecWriter::with_capacity(big_test_data.len()),
+        )
+        .void_unwrap();
+        let output2 = TestLZSS::decompress_heap(
+            SliceReader::new(&output1),
+            VecWriter::with_capacity(big_test_data.len()),
+        )
+        .void_unwrap();
+        assert_eq!(output2.as_slice(), big_test_data);
+        // the tokens are the same as with the bit format, only the last byte may differ
+        let output3 = <crate::lzss_type!(12, 4, 0x20, 2)>::compress_heap(
+            SliceReader::new(big_test_data),
+            VecWriter::with_capacity(big_test_data.len()),
+        )
+        .void_unwrap();
+        assert!(output1.len() - output3.len() <= 1);
+    }
+}
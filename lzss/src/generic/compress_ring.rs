@@ -0,0 +1,2 @@
+// The file is automatically generated by converting dynamic/compress_ring, see build.rs.
+include!(concat!(env!("OUT_DIR"), "/generic-compress_ring.rs"));
@@ -0,0 +1,2 @@
+// The file is automatically generated by converting dynamic/compress_slice, see build.rs.
+include!(concat!(env!("OUT_DIR"), "/generic-compress_slice.rs"));
@@ -0,0 +1,2 @@
+// The file is automatically generated by converting dynamic/decompress_slice, see build.rs.
+include!(concat!(env!("OUT_DIR"), "/generic-decompress_slice.rs"));
@@ -1,22 +1,35 @@
 use crate::dynamic::LzssDyn;
 use crate::error::LzssError;
+use crate::options::LzssOptions;
 use crate::read_write::{Read, Write};
-use core::convert::Infallible;
+use crate::slice::SliceWriteError;
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use alloc::vec::Vec;
 #[cfg(all(feature = "safe", not(feature = "std")))]
 use core::convert::TryInto;
+use void::Void;
 
 mod compress;
 mod compress_in_place;
+mod compress_ring;
+mod compress_slice;
 mod decompress;
+mod decompress_slice;
 
 /// A zero-sized type, the const generics specify the parameters of the compression.
 ///
+/// All functions are associated functions, a value (see [`Lzss::new`]) is only required
+/// for using it through the [`LzssCodec`](crate::LzssCodec) trait.
+///
 /// # Parameters
 /// * `EI` - The number of bits in the offset, usually `10..13`
 /// * `EJ` - The number of bits in the length, usually `4..5`
 /// * `C` - The initial fill byte of the buffer, usually `0x20` (space)
-/// * `N` - Equals `1 << EI`, the size of the buffer for [`Lzss::decompress_stack`]
+/// * `N` - Equals `1 << EI`, the size of the buffer for [`Lzss::decompress_stack`] and [`Lzss::compress_ring_stack`]
 /// * `N2` - Equals `2 << EI` (`N * 2`), the size of the buffer for [`Lzss::compress_stack`]
+/// * `P` - If a match is not longer than `P` a literal is used instead, `0` (the default)
+///   selects `(1 + EI + EJ) / 9`
+/// * `O` - The [`LzssOptions`] as bits, `0` (the default) is the original format
 ///
 /// # Restrictions
 /// * `EJ` must be larger than `0`
@@ -25,6 +38,10 @@ mod decompress;
 /// * `EI + EJ` must be 24 or less
 /// * `N` must be equal to `1 << EI`
 /// * `N2` must be equal to `2 << EI` (`N * 2`)
+/// * `1 + EI + EJ` must be at most `9 * (P + 1)`, i.e. a match must not take more than 9 bits per byte
+///   (with the default `P` this is always the case)
+/// * `(1 << EJ) + P` must be less than `N`
+/// * `O` must only contain known options
 ///
 /// All parameters are checked at compile-time.
 ///
@@ -34,6 +51,8 @@ mod decompress;
 /// Since it's not possible to do const calculations on const generics all parameters
 /// have to be set.
 ///
+/// Use [`lzss_type`](crate::lzss_type) to only specify `EI`, `EJ`, `C` and optionally `P` and `O`.
+///
 /// # Example
 /// ```rust
 /// # use lzss::{Lzss, SliceReader, SliceWriterExact};
@@ -46,13 +65,88 @@ mod decompress;
 /// );
 /// assert!(result.is_ok()); // the output is exactly 14 bytes long
 /// ```
-pub struct Lzss<const EI: usize, const EJ: usize, const C: u8, const N: usize, const N2: usize>(
-    Infallible,
-);
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Lzss<
+    const EI: usize,
+    const EJ: usize,
+    const C: u8,
+    const N: usize,
+    const N2: usize,
+    const P: usize = 0,
+    const O: u8 = 0,
+>(());
+
+/// The type of [`Lzss`] with the parameters `EI`, `EJ`, `C` and optionally `P` and the
+/// [`LzssOptions`](crate::LzssOptions), `N` and `N2` are calculated.
+///
+/// ```rust
+/// # use lzss::{lzss_type, Lzss, LzssOptions};
+/// type MyLzss = lzss_type!(10, 4, 0x20);
+/// // is the same as
+/// type MyLzss2 = Lzss<10, 4, 0x20, { 1 << 10 }, { 2 << 10 }>;
+/// // and with P
+/// type MyLzss3 = lzss_type!(12, 4, 0x20, 2);
+/// // is the same as
+/// type MyLzss4 = Lzss<12, 4, 0x20, { 1 << 12 }, { 2 << 12 }, 2>;
+/// // and with options
+/// type MyLzss5 = lzss_type!(10, 4, 0x20, 0, LzssOptions::RELATIVE);
+/// // is the same as
+/// type MyLzss6 = Lzss<10, 4, 0x20, { 1 << 10 }, { 2 << 10 }, 0, { LzssOptions::RELATIVE.bits() }>;
+/// ```
+#[macro_export]
+macro_rules! lzss_type {
+    ($ei:expr, $ej:expr, $c:expr $(,)?) => {
+        $crate::Lzss<{ $ei }, { $ej }, { $c }, { 1 << ($ei) }, { 2 << ($ei) }>
+    };
+    ($ei:expr, $ej:expr, $c:expr, $p:expr $(,)?) => {
+        $crate::Lzss<{ $ei }, { $ej }, { $c }, { 1 << ($ei) }, { 2 << ($ei) }, { $p }>
+    };
+    ($ei:expr, $ej:expr, $c:expr, $p:expr, $o:expr $(,)?) => {
+        $crate::Lzss<{ $ei }, { $ej }, { $c }, { 1 << ($ei) }, { 2 << ($ei) }, { $p }, { $crate::LzssOptions::bits($o) }>
+    };
+}
 
-impl<const EI: usize, const EJ: usize, const C: u8, const N: usize, const N2: usize>
-    Lzss<EI, EJ, C, N, N2>
+/// The parameters of the [LZSS encoder-decoder by Haruhiko Okumura](https://oku.edu.mie-u.ac.jp/~okumura/compression/lzss.c).
+///
+/// Streams are compatible to the unmodified program (`EI = 11`, `EJ = 4`, `P = 1`).
+pub type LzssOkumura = lzss_type!(11, 4, 0x20);
+
+impl<
+        const EI: usize,
+        const EJ: usize,
+        const C: u8,
+        const N: usize,
+        const N2: usize,
+        const P: usize,
+        const O: u8,
+    > Default for Lzss<EI, EJ, C, N, N2, P, O>
 {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<
+        const EI: usize,
+        const EJ: usize,
+        const C: u8,
+        const N: usize,
+        const N2: usize,
+        const P: usize,
+        const O: u8,
+    > Lzss<EI, EJ, C, N, N2, P, O>
+{
+    /// Create a value of this zero-sized type.
+    ///
+    /// This is only required for using it through the [`LzssCodec`](crate::LzssCodec) trait.
+    #[must_use]
+    pub const fn new() -> Self {
+        let _: Result<(), ()> = Self::ASSERT_PARAMETERS; // This ensures that EI+EJ are "reasonable", 1<<EI == N and 2*N == N2
+
+        Lzss(())
+    }
+
     /// Create a new [`LzssDyn`] with the parameter from this generic type.
     ///
     /// This is mainly useful for creating const [`LzssDyn`].
@@ -72,6 +166,8 @@ impl<const EI: usize, const EJ: usize, const C: u8, const N: usize, const N2: us
             ei: EI,
             ej: EJ,
             c: C,
+            p: Self::P,
+            options: Self::OPTIONS,
         }
     }
 
@@ -97,7 +193,7 @@ impl<const EI: usize, const EJ: usize, const C: u8, const N: usize, const N2: us
         let _: Result<(), ()> = Self::ASSERT_PARAMETERS; // This ensures that EI+EJ are "reasonable", 1<<EI == N and 2*N == N2
 
         let mut buffer = [C; N2];
-        Self::compress_internal(&mut reader, &mut writer, &mut buffer)?;
+        Self::compress_internal(&mut reader, &mut writer, &mut buffer, N)?;
         writer.finish().map_err(LzssError::WriteError)
     }
 
@@ -117,7 +213,7 @@ impl<const EI: usize, const EJ: usize, const C: u8, const N: usize, const N2: us
         let buffer = unsafe { &mut *(buffer.as_mut_ptr().cast::<[u8; N2]>()) };
         #[cfg(feature = "safe")]
         let buffer: &mut [u8; N2] = (&mut buffer[..]).try_into().unwrap();
-        Self::compress_internal(&mut reader, &mut writer, buffer)?;
+        Self::compress_internal(&mut reader, &mut writer, buffer, N)?;
         writer.finish().map_err(LzssError::WriteError)
     }
 
@@ -130,7 +226,96 @@ impl<const EI: usize, const EJ: usize, const C: u8, const N: usize, const N2: us
         let _: Result<(), ()> = Self::ASSERT_PARAMETERS; // This ensures that EI+EJ are "reasonable", 1<<EI == N and 2*N == N2
 
         buffer[..N - Self::F].fill(C);
-        Self::compress_internal(&mut reader, &mut writer, buffer)?;
+        Self::compress_internal(&mut reader, &mut writer, buffer, N)?;
+        writer.finish().map_err(LzssError::WriteError)
+    }
+
+    /// Compress the input data into the output, matches are at most `max_distance` bytes back.
+    ///
+    /// With [`LzssOptions::RELATIVE`] the output can be decompressed with a window of
+    /// `max_distance` bytes (rounded up to a power of two), see [`Lzss::decompress_with_window`].
+    /// The format is unchanged, any decompressor can read it.
+    ///
+    /// The buffer, with `N2` bytes, is allocated on the stack.
+    pub fn compress_with_max_distance_stack<R: Read, W: Write>(
+        mut reader: R,
+        mut writer: W,
+        max_distance: usize,
+    ) -> Result<W::Output, LzssError<R::Error, W::Error>> {
+        let _: Result<(), ()> = Self::ASSERT_PARAMETERS; // This ensures that EI+EJ are "reasonable", 1<<EI == N and 2*N == N2
+
+        let mut buffer = [C; N2];
+        Self::compress_internal(&mut reader, &mut writer, &mut buffer, max_distance)?;
+        writer.finish().map_err(LzssError::WriteError)
+    }
+
+    /// Compress the input data into the output, with a ring buffer.
+    ///
+    /// The output is identical to [`Lzss::compress_stack`], but only half the buffer is
+    /// required and no data has to be moved. Though the search is a bit slower.
+    ///
+    /// The buffer, with `N` bytes, is allocated on the stack.
+    pub fn compress_ring_stack<R: Read, W: Write>(
+        mut reader: R,
+        mut writer: W,
+    ) -> Result<W::Output, LzssError<R::Error, W::Error>> {
+        let _: Result<(), ()> = Self::ASSERT_PARAMETERS; // This ensures that EI+EJ are "reasonable", 1<<EI == N and 2*N == N2
+
+        let mut buffer = [C; N];
+        Self::compress_ring_internal(&mut reader, &mut writer, &mut buffer)?;
+        writer.finish().map_err(LzssError::WriteError)
+    }
+
+    /// Compress the input data into the output, with a ring buffer.
+    ///
+    /// The output is identical to [`Lzss::compress_heap`], but only half the buffer is
+    /// required and no data has to be moved. Though the search is a bit slower.
+    ///
+    /// The buffer, with `N` bytes, is allocated on the heap.
+    #[cfg_attr(docsrs, doc(cfg(any(feature = "alloc", feature = "std"))))]
+    #[cfg(feature = "alloc")]
+    pub fn compress_ring_heap<R: Read, W: Write>(
+        mut reader: R,
+        mut writer: W,
+    ) -> Result<W::Output, LzssError<R::Error, W::Error>> {
+        let _: Result<(), ()> = Self::ASSERT_PARAMETERS; // This ensures that EI+EJ are "reasonable", 1<<EI == N and 2*N == N2
+
+        let mut buffer = vec![C; N];
+        #[cfg(not(feature = "safe"))]
+        let buffer = unsafe { &mut *(buffer.as_mut_ptr().cast::<[u8; N]>()) };
+        #[cfg(feature = "safe")]
+        let buffer: &mut [u8; N] = (&mut buffer[..]).try_into().unwrap();
+        Self::compress_ring_internal(&mut reader, &mut writer, buffer)?;
+        writer.finish().map_err(LzssError::WriteError)
+    }
+
+    /// Compress the input data into the output, with a ring buffer.
+    ///
+    /// The output is identical to [`Lzss::compress_with_buffer`], but only half the buffer is
+    /// required and no data has to be moved. Though the search is a bit slower.
+    pub fn compress_ring_with_buffer<R: Read, W: Write>(
+        mut reader: R,
+        mut writer: W,
+        buffer: &mut [u8; N],
+    ) -> Result<W::Output, LzssError<R::Error, W::Error>> {
+        let _: Result<(), ()> = Self::ASSERT_PARAMETERS; // This ensures that EI+EJ are "reasonable", 1<<EI == N and 2*N == N2
+
+        buffer[..N - Self::F].fill(C);
+        Self::compress_ring_internal(&mut reader, &mut writer, buffer)?;
+        writer.finish().map_err(LzssError::WriteError)
+    }
+
+    /// Compress the input slice into the output.
+    ///
+    /// The output is identical to [`Lzss::compress_stack`], but no buffer is required
+    /// since the matches are searched directly in the input.
+    pub fn compress_slice<W: Write>(
+        input: &[u8],
+        mut writer: W,
+    ) -> Result<W::Output, LzssError<Void, W::Error>> {
+        let _: Result<(), ()> = Self::ASSERT_PARAMETERS; // This ensures that EI+EJ are "reasonable", 1<<EI == N and 2*N == N2
+
+        Self::compress_slice_internal(input, &mut writer).map_err(LzssError::WriteError)?;
         writer.finish().map_err(LzssError::WriteError)
     }
 
@@ -193,6 +378,55 @@ impl<const EI: usize, const EJ: usize, const C: u8, const N: usize, const N2: us
         writer.finish().map_err(LzssError::WriteError)
     }
 
+    /// Decompress the input data into the output, with a window smaller than `N`.
+    ///
+    /// This requires [`LzssOptions::RELATIVE`] (or [`LzssOptions::ADAPTIVE_OFFSET`]) and no
+    /// distance in the input may be larger than the window, e.g. it was compressed with
+    /// [`Lzss::compress_with_max_distance_stack`]. Larger distances are not detected, the output is wrong then.
+    ///
+    /// It will be asserted at runtime that the options are relative and that the window is
+    /// a power of two, but not larger than `N`.
+    pub fn decompress_with_window<R: Read, W: Write>(
+        mut reader: R,
+        mut writer: W,
+        window: &mut [u8],
+    ) -> Result<W::Output, LzssError<R::Error, W::Error>> {
+        let _: Result<(), ()> = Self::ASSERT_PARAMETERS; // This ensures that EI+EJ are "reasonable", 1<<EI == N and 2*N == N2
+
+        assert!(Self::OPTIONS.is_relative());
+        assert!(window.len().is_power_of_two() && window.len() <= N);
+        window.fill(C);
+        Self::decompress_window_internal(&mut reader, &mut writer, window)?;
+        writer.finish().map_err(LzssError::WriteError)
+    }
+
+    /// Decompress the input data into the output slice.
+    ///
+    /// No buffer is required, the already written output is used instead.
+    ///
+    /// Returns the number of written bytes, or [`SliceWriteError`] in case the output is too small.
+    pub fn decompress_to_slice<R: Read>(
+        mut reader: R,
+        output: &mut [u8],
+    ) -> Result<usize, LzssError<R::Error, SliceWriteError>> {
+        let _: Result<(), ()> = Self::ASSERT_PARAMETERS; // This ensures that EI+EJ are "reasonable", 1<<EI == N and 2*N == N2
+
+        Self::decompress_to_slice_internal(&mut reader, output)
+    }
+
+    /// Decompress the input data into a new vector.
+    ///
+    /// No buffer is required, the already written output is used instead.
+    #[cfg_attr(docsrs, doc(cfg(any(feature = "alloc", feature = "std"))))]
+    #[cfg(feature = "alloc")]
+    pub fn decompress_to_vec<R: Read>(mut reader: R) -> Result<Vec<u8>, LzssError<R::Error, Void>> {
+        let _: Result<(), ()> = Self::ASSERT_PARAMETERS; // This ensures that EI+EJ are "reasonable", 1<<EI == N and 2*N == N2
+
+        let mut output = Vec::new();
+        Self::decompress_to_vec_internal(&mut reader, &mut output).map_err(LzssError::ReadError)?;
+        Ok(output)
+    }
+
     /// Compress, the input and output is in the same slice.
     ///
     /// The input is located at `io[offset..]`.
@@ -203,7 +437,8 @@ impl<const EI: usize, const EJ: usize, const C: u8, const N: usize, const N2: us
     /// the input, then the result will be `(size, Some(new_offset))`, the already compressed
     /// data is in `io[0..size]` and the not yet compressed data is in `io[new_offset..]`.
     ///
-    /// Even when the compression fails due to space the data is recoverable.
+    /// Even when the compression fails due to space the data is recoverable,
+    /// see [`Lzss::compress_in_place_continue_stack`].
     ///
     /// The minimum offset is [`Lzss::MIN_OFFSET`], though if the offset is `Lzss::MIN_OFFSET + input_size/8`
     /// then the compression can't fail.
@@ -218,11 +453,97 @@ impl<const EI: usize, const EJ: usize, const C: u8, const N: usize, const N2: us
     /// It's a little less than `N`.
     pub const MIN_OFFSET: usize = (N - Self::F) + Self::MIN_GAP_SIZE;
 
+    /// Continue a `compress_in_place` which returned `(size, Some(new_offset))`.
+    ///
+    /// The not yet compressed data at `io[new_offset..]` is compressed into the writer.
+    /// Since the last byte of the already compressed data may be incomplete, it is written
+    /// again, i.e. the complete compressed data is `io[0..size - 1]` (or nothing if `size` is `0`)
+    /// followed by the output of the writer.
+    ///
+    /// `io[0..size]` and `io[new_offset..]` must be the unaltered data from `compress_in_place`,
+    /// as well as the data in front of `new_offset` (about `1 << EI` bytes).
+    ///
+    /// The buffer, with `N2` bytes, is allocated on the stack.
+    pub fn compress_in_place_continue_stack<W: Write>(
+        io: &[u8],
+        size: usize,
+        new_offset: usize,
+        mut writer: W,
+    ) -> Result<W::Output, LzssError<Void, W::Error>> {
+        let _: Result<(), ()> = Self::ASSERT_PARAMETERS; // This ensures that EI+EJ are "reasonable", 1<<EI == N and 2*N == N2
+
+        let mut buffer = [C; N2];
+        Self::compress_in_place_continue_internal(io, size, new_offset, &mut writer, &mut buffer)?;
+        writer.finish().map_err(LzssError::WriteError)
+    }
+
+    /// Continue a `compress_in_place` which returned `(size, Some(new_offset))`.
+    ///
+    /// The not yet compressed data at `io[new_offset..]` is compressed into the writer.
+    /// Since the last byte of the already compressed data may be incomplete, it is written
+    /// again, i.e. the complete compressed data is `io[0..size - 1]` (or nothing if `size` is `0`)
+    /// followed by the output of the writer.
+    ///
+    /// `io[0..size]` and `io[new_offset..]` must be the unaltered data from `compress_in_place`,
+    /// as well as the data in front of `new_offset` (about `1 << EI` bytes).
+    pub fn compress_in_place_continue_with_buffer<W: Write>(
+        io: &[u8],
+        size: usize,
+        new_offset: usize,
+        mut writer: W,
+        buffer: &mut [u8; N2],
+    ) -> Result<W::Output, LzssError<Void, W::Error>> {
+        let _: Result<(), ()> = Self::ASSERT_PARAMETERS; // This ensures that EI+EJ are "reasonable", 1<<EI == N and 2*N == N2
+
+        Self::compress_in_place_continue_internal(io, size, new_offset, &mut writer, buffer)?;
+        writer.finish().map_err(LzssError::WriteError)
+    }
+
+    /// Decompress, the input and output is in the same slice.
+    ///
+    /// The input is located at `io[compressed_offset..compressed_offset + compressed_len]`
+    /// and the output is written to `io[0..size]`, the result is `Ok(size)`.
+    ///
+    /// When the output would overwrite not yet read input (or the input is not within the slice)
+    /// then [`SliceWriteError`] is returned.
+    ///
+    /// Placing the input at the end of a slice with the size `uncompressed_len +
+    /// Lzss::decompress_in_place_margin(uncompressed_len)` ensures that this can't happen.
+    pub fn decompress_in_place(
+        io: &mut [u8],
+        compressed_offset: usize,
+        compressed_len: usize,
+    ) -> Result<usize, SliceWriteError> {
+        let _: Result<(), ()> = Self::ASSERT_PARAMETERS; // This ensures that EI+EJ are "reasonable", 1<<EI == N and 2*N == N2
+
+        Self::decompress_in_place_internal(io, compressed_offset, compressed_len)
+    }
+
+    /// The number of bytes which the slice for `decompress_in_place` has to be larger than
+    /// the decompressed data.
+    ///
+    /// With [`LzssOptions::END_MARKER`] it includes the end marker, but not the flush
+    /// markers of [`LzssCompressor::flush`](crate::LzssCompressor::flush) (up to 4 bytes each).
+    #[must_use]
+    pub const fn decompress_in_place_margin(uncompressed_len: usize) -> usize {
+        crate::dynamic::decompress_in_place_margin(Self::OPTIONS, uncompressed_len)
+    }
+
     // non-public helpers
 
-    pub(crate) const P: usize = (1 + EI + EJ) / 9; /* If match length <= P then output one character */
+    pub(crate) const P: usize = if P == 0 { (1 + EI + EJ) / 9 } else { P }; /* If match length <= P then output one character */
     pub(crate) const F: usize = (1 << EJ) + Self::P; /* lookahead buffer size */
     pub(crate) const MIN_GAP_SIZE: usize = Self::P + 4;
+    pub(crate) const OPTIONS: LzssOptions = match LzssOptions::from_bits(O) {
+        Some(options) => options,
+        None => panic!("LZSS: Invalid O, unknown options"),
+    };
+
+    // The width of the offset after `seen` bytes, see LzssOptions::ADAPTIVE_OFFSET.
+    #[inline(always)]
+    pub(crate) const fn offset_bits(seen: usize) -> usize {
+        Self::OPTIONS.offset_bits(EI, EJ, seen)
+    }
 
     const ASSERT_PARAMETERS: Result<(), ()> = {
         if EJ == 0 {
@@ -247,6 +568,13 @@ impl<const EI: usize, const EJ: usize, const C: u8, const N: usize, const N2: us
         if N2 != 2 * N {
             panic!("LZSS: Invalid N2, must be exactly 2*N")
         }
+        if 1 + EI + EJ > 9 * (Self::P + 1) {
+            panic!("LZSS: Invalid P, a match must not take more than 9 bits per byte")
+        }
+        if Self::F >= N {
+            panic!("LZSS: Invalid P, (1 << EJ) + P must be less than N")
+        }
+        let _: LzssOptions = Self::OPTIONS;
         Ok(())
     };
 }
@@ -256,7 +584,7 @@ mod tests {
     use crate::generic::Lzss;
     use crate::slice::SliceReader;
     use crate::vec::VecWriter;
-    use crate::void::ResultLzssErrorVoidExt;
+    use crate::void::{ResultLzssErrorVoidExt, ResultLzssErrorVoidReadExt};
 
     type TestLZSS = Lzss<10, 4, 0x20, { 1 << 10 }, { 2 << 10 }>;
 
@@ -288,6 +616,15 @@ mod tests {
         assert_eq!(output.as_slice(), TEST_DATA);
     }
 
+    #[test]
+    fn test_decompress_to_slice() {
+        let mut output = [0u8; TEST_DATA.len()];
+        let size = TestLZSS::decompress_to_slice(SliceReader::new(&COMPRESSED_DATA), &mut output)
+            .void_read_unwrap();
+        assert_eq!(size, Ok(TEST_DATA.len()));
+        assert_eq!(&output, TEST_DATA);
+    }
+
     #[test]
     fn test_compress() {
         let output = TestLZSS::compress_stack(
@@ -310,6 +647,33 @@ mod tests {
         assert_eq!(output.as_slice(), COMPRESSED_DATA);
     }
 
+    #[test]
+    fn test_compress_ring() {
+        let output = TestLZSS::compress_ring_stack(
+            SliceReader::new(TEST_DATA),
+            VecWriter::with_capacity(COMPRESSED_DATA.len()),
+        )
+        .void_unwrap();
+        assert_eq!(output.as_slice(), COMPRESSED_DATA);
+    }
+
+    #[test]
+    fn test_compress_slice() {
+        let output =
+            TestLZSS::compress_slice(TEST_DATA, VecWriter::with_capacity(COMPRESSED_DATA.len()))
+                .void_unwrap();
+        assert_eq!(output.as_slice(), COMPRESSED_DATA);
+        // matches which are starting in the initial fill
+        for input in [&b""[..], b"   ", b"  Sample  ", &[b' '; 40]] {
+            let output1 =
+                TestLZSS::compress_stack(SliceReader::new(input), VecWriter::with_capacity(40))
+                    .void_unwrap();
+            let output2 =
+                TestLZSS::compress_slice(input, VecWriter::with_capacity(40)).void_unwrap();
+            assert_eq!(output1, output2);
+        }
+    }
+
     #[test]
     fn test_compress_in_place() {
         const OFFSET: usize = TestLZSS::MIN_OFFSET + TEST_DATA.len() / 8;
@@ -321,6 +685,57 @@ mod tests {
         assert_eq!(io[0..c], COMPRESSED_DATA);
     }
 
+    #[test]
+    fn test_compress_in_place_continue() {
+        let big_test_data = include_bytes!("mod.rs");
+        let output1 = TestLZSS::compress_stack(
+            SliceReader::new(big_test_data),
+            VecWriter::with_capacity(big_test_data.len()),
+        )
+        .void_unwrap();
+        for offset in [0, 1, TestLZSS::MIN_OFFSET / 2, TestLZSS::MIN_OFFSET - 1] {
+            let mut io = vec![0; offset + big_test_data.len()];
+            io[offset..].copy_from_slice(big_test_data);
+            let (c, u) = TestLZSS::compress_in_place(&mut io, offset);
+            let u = u.unwrap();
+            let output2 = TestLZSS::compress_in_place_continue_stack(
+                &io,
+                c,
+                u,
+                VecWriter::with_capacity(big_test_data.len()),
+            )
+            .void_unwrap();
+            assert_eq!(&output1[..c.saturating_sub(1)], &io[..c.saturating_sub(1)]);
+            assert_eq!(&output1[c.saturating_sub(1)..], output2.as_slice());
+        }
+        // the compressible data followed by hardly compressible data, thus it fails partway
+        let mut input = big_test_data[..4000].to_vec();
+        let mut x: u32 = 1;
+        for _ in 0..30000 {
+            x = x.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            input.push((x >> 16) as u8);
+        }
+        let offset = TestLZSS::MIN_OFFSET + 100;
+        let mut io = vec![0; offset + input.len()];
+        io[offset..].copy_from_slice(&input);
+        let (c, u) = TestLZSS::compress_in_place(&mut io, offset);
+        let u = u.unwrap();
+        assert!(c > 2000);
+        let output2 = TestLZSS::compress_in_place_continue_stack(
+            &io,
+            c,
+            u,
+            VecWriter::with_capacity(input.len()),
+        )
+        .void_unwrap();
+        let mut combined = io[..c - 1].to_vec();
+        combined.extend(output2);
+        assert_eq!(
+            TestLZSS::decompress_to_vec(SliceReader::new(&combined)).void_unwrap(),
+            input
+        );
+    }
+
     #[test]
     fn test_compress_big() {
         let big_test_data = include_bytes!("mod.rs");
@@ -339,6 +754,18 @@ mod tests {
         assert_eq!(u, None);
         // compare both
         assert_eq!(output1.as_slice(), &io[0..c]);
+        // compress_ring
+        let output4 = TestLZSS::compress_ring_heap(
+            SliceReader::new(big_test_data),
+            VecWriter::with_capacity(big_test_data.len()),
+        )
+        .void_unwrap();
+        assert_eq!(output1, output4);
+        // compress_slice
+        let output5 =
+            TestLZSS::compress_slice(big_test_data, VecWriter::with_capacity(big_test_data.len()))
+                .void_unwrap();
+        assert_eq!(output1, output5);
         // decompress
         let output2 = TestLZSS::decompress_stack(
             SliceReader::new(&io[0..c]),
@@ -346,5 +773,77 @@ mod tests {
         )
         .void_unwrap();
         assert_eq!(output2.as_slice(), big_test_data);
+        // decompress_to_vec
+        let output3 = TestLZSS::decompress_to_vec(SliceReader::new(&io[0..c])).void_unwrap();
+        assert_eq!(output3.as_slice(), big_test_data);
+        // decompress_in_place
+        let mut io2 = vec![
+            0;
+            big_test_data.len()
+                + TestLZSS::decompress_in_place_margin(big_test_data.len())
+        ];
+        let offset = io2.len() - c;
+        io2[offset..].copy_from_slice(&io[0..c]);
+        assert_eq!(
+            TestLZSS::decompress_in_place(&mut io2, offset, c),
+            Ok(big_test_data.len())
+        );
+        assert_eq!(&io2[..big_test_data.len()], big_test_data);
+    }
+
+    #[test]
+    fn test_compress_big_all_options() {
+        type OptionsLZSS = Lzss<10, 4, 0x20, { 1 << 10 }, { 2 << 10 }, 2, 15>;
+        let big_test_data = include_bytes!("mod.rs");
+        // the generic and the dynamic version create the same output
+        let output1 = OptionsLZSS::compress_heap(
+            SliceReader::new(big_test_data),
+            VecWriter::with_capacity(big_test_data.len()),
+        )
+        .void_unwrap();
+        let output2 = OptionsLZSS::as_dyn()
+            .compress(
+                SliceReader::new(big_test_data),
+                VecWriter::with_capacity(big_test_data.len()),
+            )
+            .void_unwrap();
+        assert_eq!(output1, output2);
+        // compress_in_place
+        let offset: usize = OptionsLZSS::MIN_OFFSET + big_test_data.len() / 8;
+        let mut io = vec![0; offset + big_test_data.len()];
+        io[offset..].copy_from_slice(big_test_data);
+        let (c, u) = OptionsLZSS::compress_in_place(&mut io, offset);
+        assert_eq!(u, None);
+        assert_eq!(output1.as_slice(), &io[0..c]);
+        // compress_ring
+        let output3 = OptionsLZSS::compress_ring_heap(
+            SliceReader::new(big_test_data),
+            VecWriter::with_capacity(big_test_data.len()),
+        )
+        .void_unwrap();
+        assert_eq!(output1, output3);
+        // compress_slice
+        let output4 = OptionsLZSS::compress_slice(
+            big_test_data,
+            VecWriter::with_capacity(big_test_data.len()),
+        )
+        .void_unwrap();
+        assert_eq!(output1, output4);
+        // decompress_to_vec
+        let output5 = OptionsLZSS::decompress_to_vec(SliceReader::new(&output1)).void_unwrap();
+        assert_eq!(output5.as_slice(), big_test_data);
+        // decompress_in_place
+        let mut io2 = vec![
+            0;
+            big_test_data.len()
+                + OptionsLZSS::decompress_in_place_margin(big_test_data.len())
+        ];
+        let offset = io2.len() - output1.len();
+        io2[offset..].copy_from_slice(&output1);
+        assert_eq!(
+            OptionsLZSS::decompress_in_place(&mut io2, offset, output1.len()),
+            Ok(big_test_data.len())
+        );
+        assert_eq!(&io2[..big_test_data.len()], big_test_data);
     }
 }
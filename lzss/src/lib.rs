@@ -39,6 +39,51 @@
 //! (The same applies for decompress and other functions, only used function will
 //! be in the generated program.)
 //!
+//! Both implement [`LzssCodec`](crate::LzssCodec), so code can be written once for either of them.
+//!
+//! When the parameters are only known at runtime but usually are one of a few sets,
+//! [`lzss_dispatch`](crate::lzss_dispatch) can be used to call the generic code for those.
+//!
+//! # Flag-byte format
+//!
+//! Besides the bitstream of this crate, the byte-aligned format of the original `LZSS.C`
+//! (eight flags in a byte, followed by 8-bit literals and 16-bit matches) is supported
+//! with [`LzssFlagByte`](crate::LzssFlagByte).
+//!
+//! [`LzssComplzss`](crate::LzssComplzss) adds the Apple `complzss` header (with lengths and an
+//! Adler-32 checksum) to it, as used by kernelcaches and firmware images.
+//!
+//! The Nintendo GBA/DS BIOS formats (with a header, msb-first flags and a 12-bit displacement)
+//! are supported with [`LzssLz10`](crate::LzssLz10) and [`LzssLz11`](crate::LzssLz11).
+//!
+//! # Format options
+//!
+//! [`LzssOptions`](crate::LzssOptions) can change the format of the stream, e.g. to use backward
+//! distances instead of absolute buffer positions. Both sides must use the same options.
+//!
+//! [`LzssCompressor`](crate::LzssCompressor) compresses a continuous stream piece by piece,
+//! with flush points which make the data so far decodable (using the end marker option).
+//! [`LzssDecompressor`](crate::LzssDecompressor) is the counterpart, the compressed data is
+//! passed piece by piece. The state of both can be saved as a checkpoint and restored later.
+//!
+//! [`LzssDyn::compress_delta`](crate::LzssDyn::compress_delta) compresses data against a
+//! reference (e.g. a firmware update against the installed image), the decoder reads the
+//! reference through the random-access [`ReadAt`](crate::ReadAt) trait.
+//!
+//! [`LzssDyn::compress_words`](crate::LzssDyn::compress_words) uses 16-bit words instead of
+//! bytes as symbols, for data which repeats at 16-bit granularity (like tilemaps or audio samples).
+//! [`LzssDyn::compress_huffman`](crate::LzssDyn::compress_huffman) encodes the tokens with
+//! canonical huffman codes per block, the decoder needs no allocation.
+//!
+//! # Filters
+//!
+//! Filters are reversible conversions which make data more compressible, they wrap a
+//! [`Read`](crate::Read) in front of a compression or a [`Write`](crate::Write) after a
+//! decompression. [`BcjReader`](crate::BcjReader) and [`BcjWriter`](crate::BcjWriter) convert
+//! the relative addresses of calls in executables into absolute ones (see [`BcjFilter`](crate::BcjFilter)).
+//! [`DeltaReader`](crate::DeltaReader) and [`DeltaWriter`](crate::DeltaWriter) store the
+//! differences of numeric data, e.g. sensor logs or tables (see [`DeltaFilter`](crate::DeltaFilter)).
+//!
 //! # Lack of a header
 //!
 //! This algorithm has by design no header at all. Please be aware that it is not
@@ -49,11 +94,14 @@
 //! This code is based on the [LZSS encoder-decoder by Haruhiko Okumura, public domain](https://oku.edu.mie-u.ac.jp/~okumura/compression/lzss.c).
 //!
 //! In order to create an encoder-decoder which is compatible to the program above
-//! the following is required: `C = 0x20` in this library and `P = (1+EI+EJ) / 9` in Okumuras program.
+//! the following is required: `C = 0x20` in this library and `P = (1+EI+EJ) / 9` in Okumuras program
+//! (or the same custom `P` in both).
+//! [`LzssOkumura`](crate::LzssOkumura) has the parameters of the unmodified program.
 //!
 //! # Features
 //! * `alloc`       - Allows de-/compression with buffer on the heap and the [`VecWriter`](crate::VecWriter).
 //! * `safe`        - Only use safe code (see Safety below).
+//! * `serde`       - (De-)serialization of [`LzssDyn`](crate::LzssDyn).
 //! * `std`         - Enables `alloc` and additional [`IOSimpleReader`](crate::IOSimpleReader), [`IOSimpleWriter`](crate::IOSimpleWriter),
 //!   and the [`Error`](::std::error::Error) instance for [`LzssError`](crate::LzssError) and [`LzssDynError`](crate::LzssDynError).
 //!
@@ -74,8 +122,8 @@
 //!
 //! # Example
 //! ```rust
-//! # use lzss::{Lzss, SliceReader, SliceWriter};
-//! type MyLzss = Lzss<10, 4, 0x20, { 1 << 10 }, { 2 << 10 }>;
+//! # use lzss::{lzss_type, SliceReader, SliceWriter};
+//! type MyLzss = lzss_type!(10, 4, 0x20); // = Lzss<10, 4, 0x20, { 1 << 10 }, { 2 << 10 }>
 //! let input = b"Example Data";
 //! let mut output = [0; 30];
 //! let result = MyLzss::compress_stack(
@@ -97,12 +145,22 @@
 #[macro_use]
 extern crate alloc;
 
-pub use crate::dynamic::{LzssDyn, LzssDynError};
+pub use crate::bcj::{BcjFilter, BcjReader, BcjWriter};
+pub use crate::checkpoint::LzssCheckpointError;
+pub use crate::codec::LzssCodec;
+pub use crate::complzss::{LzssComplzss, LzssComplzssError};
+pub use crate::compressor::{LzssCompressor, LzssFlushError};
+pub use crate::decompressor::LzssDecompressor;
+pub use crate::delta_filter::{DeltaFilter, DeltaReader, DeltaWriter};
+pub use crate::dynamic::{LzssDeltaError, LzssDyn, LzssDynError, LzssHuffmanError, LzssWordError};
 pub use crate::error::LzssError;
-pub use crate::generic::Lzss;
+pub use crate::flag_byte::LzssFlagByte;
+pub use crate::generic::{Lzss, LzssOkumura};
 #[cfg(feature = "std")]
 pub use crate::io_simple::{IOSimpleReader, IOSimpleWriter};
-pub use crate::read_write::{Read, Write};
+pub use crate::nintendo::{LzssLz10, LzssLz11, LzssNintendoError};
+pub use crate::options::LzssOptions;
+pub use crate::read_write::{Read, ReadAt, Write};
 pub use crate::slice::{SliceReader, SliceWriteError, SliceWriter, SliceWriterExact};
 #[cfg(feature = "alloc")]
 pub use crate::vec::VecWriter;
@@ -110,13 +168,24 @@ pub use crate::void::{
     ResultLzssErrorVoidExt, ResultLzssErrorVoidReadExt, ResultLzssErrorVoidWriteExt,
 };
 
+mod bcj;
 mod bits;
+mod checkpoint;
+mod codec;
+mod complzss;
+mod compressor;
+mod decompressor;
+mod delta_filter;
+mod dispatch;
 mod dynamic;
 mod error;
+mod flag_byte;
 mod generic;
 #[cfg(feature = "std")]
 mod io_simple;
 mod macros;
+mod nintendo;
+mod options;
 mod read_write;
 #[cfg_attr(feature = "safe", path = "slice_safe.rs")]
 mod slice;
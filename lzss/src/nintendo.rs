@@ -0,0 +1,465 @@
+use crate::macros::{get, set};
+use crate::read_write::{Read, Write};
+#[cfg(all(feature = "safe", feature = "alloc", not(feature = "std")))]
+use core::convert::TryInto;
+use core::fmt::Display;
+use void::Void;
+
+const N: usize = 4096;
+const MAX_SIZE: usize = 0xff_ffff;
+
+/// The Nintendo LZ10 format (GBA/DS BIOS `LZ77UnComp`).
+///
+/// The four byte header contains `0x10` and the 24-bit length of the uncompressed data
+/// (little-endian). Eight flags are grouped into a byte (msb first, `1` is a match) which
+/// is followed by the eight byte-aligned tokens. A literal is one byte, a match is two bytes
+/// with a 4-bit length (`3..=18`) and a 12-bit displacement (`1..=4096`, big-endian):
+/// `((length - 3) << 4) | ((displacement - 1) >> 8)`, `(displacement - 1) & 0xff`.
+///
+/// # Example
+/// ```rust
+/// # use lzss::{LzssLz10, SliceReader, VecWriter};
+/// let input = b"abcabcabc";
+/// let compressed = LzssLz10::compress(input, VecWriter::with_capacity(30)).unwrap();
+/// assert_eq!(compressed, [0x10, 9, 0, 0, 0x10, b'a', b'b', b'c', 0x30, 0x02]);
+/// let decompressed = LzssLz10::decompress_stack(
+///     SliceReader::new(&compressed),
+///     VecWriter::with_capacity(9),
+/// );
+/// assert_eq!(decompressed.unwrap(), input);
+/// ```
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct LzssLz10(());
+
+/// The Nintendo LZ11 format (DS and later).
+///
+/// It's the same as [`LzssLz10`] (with `0x11` in the header), except that a match may be longer.
+/// The upper four bits of the first byte of a match select the encoding:
+/// * `0`: three bytes, `8-bit length - 0x11` and 12-bit displacement
+/// * `1`: four bytes, `16-bit length - 0x111` and 12-bit displacement
+/// * else: two bytes, `4-bit length - 1` and 12-bit displacement
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct LzssLz11(());
+
+macro_rules! nintendo_impl {
+    ($name:ident, $lz11:expr) => {
+        impl $name {
+            /// Compress the input into the output.
+            ///
+            /// No buffer is required, the matches are searched directly in the input.
+            pub fn compress<W: Write>(
+                input: &[u8],
+                mut writer: W,
+            ) -> Result<W::Output, LzssNintendoError<Void, W::Error>> {
+                compress_internal(input, &mut writer, $lz11, 1)?;
+                writer.finish().map_err(LzssNintendoError::WriteError)
+            }
+
+            /// Compress the input into the output, without using a displacement of 1.
+            ///
+            /// The output can be decompressed directly into VRAM, which only supports
+            /// 16-bit writes, i.e. the previous byte can't be read back yet.
+            pub fn compress_vram_safe<W: Write>(
+                input: &[u8],
+                mut writer: W,
+            ) -> Result<W::Output, LzssNintendoError<Void, W::Error>> {
+                compress_internal(input, &mut writer, $lz11, 2)?;
+                writer.finish().map_err(LzssNintendoError::WriteError)
+            }
+
+            /// Decompress the input data into the output.
+            ///
+            /// The buffer, with 4096 bytes, is allocated on the stack.
+            pub fn decompress_stack<R: Read, W: Write>(
+                mut reader: R,
+                mut writer: W,
+            ) -> Result<W::Output, LzssNintendoError<R::Error, W::Error>> {
+                let mut buffer = [0; N];
+                decompress_internal(&mut reader, &mut writer, &mut buffer, $lz11)?;
+                writer.finish().map_err(LzssNintendoError::WriteError)
+            }
+
+            /// Decompress the input data into the output.
+            ///
+            /// The buffer, with 4096 bytes, is allocated on the heap.
+            #[cfg_attr(docsrs, doc(cfg(any(feature = "alloc", feature = "std"))))]
+            #[cfg(feature = "alloc")]
+            pub fn decompress_heap<R: Read, W: Write>(
+                mut reader: R,
+                mut writer: W,
+            ) -> Result<W::Output, LzssNintendoError<R::Error, W::Error>> {
+                let mut buffer = vec![0; N];
+                #[cfg(not(feature = "safe"))]
+                let buffer = unsafe { &mut *(buffer.as_mut_ptr().cast::<[u8; N]>()) };
+                #[cfg(feature = "safe")]
+                let buffer: &mut [u8; N] = (&mut buffer[..]).try_into().unwrap();
+                decompress_internal(&mut reader, &mut writer, buffer, $lz11)?;
+                writer.finish().map_err(LzssNintendoError::WriteError)
+            }
+
+            /// Decompress the input data into the output.
+            pub fn decompress_with_buffer<R: Read, W: Write>(
+                mut reader: R,
+                mut writer: W,
+                buffer: &mut [u8; 4096],
+            ) -> Result<W::Output, LzssNintendoError<R::Error, W::Error>> {
+                decompress_internal(&mut reader, &mut writer, buffer, $lz11)?;
+                writer.finish().map_err(LzssNintendoError::WriteError)
+            }
+        }
+    };
+}
+
+nintendo_impl!(LzssLz10, false);
+nintendo_impl!(LzssLz11, true);
+
+/// The error returned by [`LzssLz10`] and [`LzssLz11`].
+#[derive(Debug, Eq, PartialEq)]
+pub enum LzssNintendoError<R, W> {
+    /// Contains the read error value.
+    ReadError(R),
+    /// Contains the write error value.
+    WriteError(W),
+    /// The header has not the expected type (`0x10` or `0x11`).
+    InvalidHeader,
+    /// The input ended before the data was complete.
+    Truncated,
+    /// A match is referring to data before the start of the output.
+    InvalidDisplacement,
+    /// The data is larger than 16 MiB, the length doesn't fit into the header.
+    TooLarge,
+}
+
+impl<R: Display, W: Display> core::fmt::Display for LzssNintendoError<R, W> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            LzssNintendoError::ReadError(error) => write!(f, "Read error: {error}"),
+            LzssNintendoError::WriteError(error) => write!(f, "Write error: {error}"),
+            LzssNintendoError::InvalidHeader => f.write_str("Invalid header"),
+            LzssNintendoError::Truncated => f.write_str("Truncated input"),
+            LzssNintendoError::InvalidDisplacement => f.write_str("Invalid displacement"),
+            LzssNintendoError::TooLarge => f.write_str("Data too large"),
+        }
+    }
+}
+
+/// Implementation of [`Error`](std::error::Error) for [`LzssNintendoError`]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[cfg(feature = "std")]
+impl<R, W> std::error::Error for LzssNintendoError<R, W>
+where
+    R: std::error::Error + 'static,
+    W: std::error::Error + 'static,
+{
+    #[inline]
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LzssNintendoError::ReadError(error) => Some(error),
+            LzssNintendoError::WriteError(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+// Allow many single char names, the search uses the names of find_match (c, i, j, x, y).
+#[allow(clippy::many_single_char_names)]
+#[inline(always)]
+fn compress_internal<W: Write>(
+    input: &[u8],
+    writer: &mut W,
+    lz11: bool,
+    min_displacement: usize,
+) -> Result<(), LzssNintendoError<Void, W::Error>> {
+    if input.len() > MAX_SIZE {
+        return Err(LzssNintendoError::TooLarge);
+    }
+    let f = if lz11 { 0x111 + 0xffff } else { 18 };
+
+    let header = (input.len() << 8) as u32 | if lz11 { 0x11 } else { 0x10 };
+    for b in header.to_le_bytes() {
+        writer.write(b).map_err(LzssNintendoError::WriteError)?;
+    }
+
+    // code_buf[0] contains the flags, followed by up to eight tokens (at most four bytes each)
+    let mut code_buf = [0u8; 33];
+    let mut code_len = 1;
+    let mut mask = 0x80u8;
+
+    let mut r = 0;
+    while r < input.len() {
+        let look_ahead = &input[r..input.len().min(r + f)];
+        let mut x = 0;
+        let mut y = 1;
+        let c = get!(look_ahead, 0);
+        // Nothing can be referenced until at least min_displacement bytes are written
+        if let Some(last) = r.checked_sub(min_displacement) {
+            for i in (r.saturating_sub(N)..=last).rev() {
+                if get!(input, i) == c {
+                    let j = 1 + look_ahead[1..]
+                        .iter()
+                        .zip(&input[i + 1..])
+                        .take_while(|(a, b)| a == b)
+                        .count();
+                    if j > y {
+                        x = i;
+                        y = j;
+                        if y == look_ahead.len() {
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+        if y < 3 {
+            code_buf[code_len] = c;
+            code_len += 1;
+            y = 1;
+        } else {
+            code_buf[0] |= mask;
+            let d = r - x - 1;
+            if !lz11 {
+                code_buf[code_len] = (((y - 3) << 4) | (d >> 8)) as u8;
+                code_buf[code_len + 1] = d as u8;
+                code_len += 2;
+            } else if y <= 0x10 {
+                code_buf[code_len] = (((y - 1) << 4) | (d >> 8)) as u8;
+                code_buf[code_len + 1] = d as u8;
+                code_len += 2;
+            } else if y <= 0x110 {
+                let l = y - 0x11;
+                code_buf[code_len] = (l >> 4) as u8;
+                code_buf[code_len + 1] = ((l << 4) | (d >> 8)) as u8;
+                code_buf[code_len + 2] = d as u8;
+                code_len += 3;
+            } else {
+                let l = y - 0x111;
+                code_buf[code_len] = (0x10 | (l >> 12)) as u8;
+                code_buf[code_len + 1] = (l >> 4) as u8;
+                code_buf[code_len + 2] = ((l << 4) | (d >> 8)) as u8;
+                code_buf[code_len + 3] = d as u8;
+                code_len += 4;
+            }
+        }
+        mask >>= 1;
+        if mask == 0 {
+            for &b in &code_buf[..code_len] {
+                writer.write(b).map_err(LzssNintendoError::WriteError)?;
+            }
+            code_buf[0] = 0;
+            code_len = 1;
+            mask = 0x80;
+        }
+        r += y;
+    }
+    if code_len > 1 {
+        for &b in &code_buf[..code_len] {
+            writer.write(b).map_err(LzssNintendoError::WriteError)?;
+        }
+    }
+    Ok(())
+}
+
+#[inline(always)]
+fn read_byte<R: Read, W>(reader: &mut R) -> Result<usize, LzssNintendoError<R::Error, W>> {
+    match reader.read().map_err(LzssNintendoError::ReadError)? {
+        None => Err(LzssNintendoError::Truncated),
+        Some(b) => Ok(b as usize),
+    }
+}
+
+#[inline(always)]
+fn decompress_internal<R: Read, W: Write>(
+    reader: &mut R,
+    writer: &mut W,
+    buffer: &mut [u8; N],
+    lz11: bool,
+) -> Result<(), LzssNintendoError<R::Error, W::Error>> {
+    let header_type = read_byte(reader)?;
+    if header_type != if lz11 { 0x11 } else { 0x10 } {
+        return Err(LzssNintendoError::InvalidHeader);
+    }
+    let size = read_byte(reader)? | (read_byte(reader)? << 8) | (read_byte(reader)? << 16);
+
+    let mut o = 0;
+    let mut flags = 0;
+    let mut mask = 0;
+    while o < size {
+        if mask == 0 {
+            flags = read_byte(reader)?;
+            mask = 0x80;
+        }
+        if (flags & mask) == 0 {
+            let c = read_byte(reader)? as u8;
+            writer.write(c).map_err(LzssNintendoError::WriteError)?;
+            set!(buffer, o & (N - 1), c);
+            o += 1;
+        } else {
+            let b0 = read_byte(reader)?;
+            let (len, b) = if lz11 {
+                match b0 >> 4 {
+                    0 => {
+                        let b1 = read_byte(reader)?;
+                        ((((b0 & 0x0f) << 4) | (b1 >> 4)) + 0x11, b1)
+                    }
+                    1 => {
+                        let b1 = read_byte(reader)?;
+                        let b2 = read_byte(reader)?;
+                        ((((b0 & 0x0f) << 12) | (b1 << 4) | (b2 >> 4)) + 0x111, b2)
+                    }
+                    i => (i + 1, b0),
+                }
+            } else {
+                ((b0 >> 4) + 3, b0)
+            };
+            let d = (((b & 0x0f) << 8) | read_byte(reader)?) + 1;
+            if d > o {
+                return Err(LzssNintendoError::InvalidDisplacement);
+            }
+            for _ in 0..len.min(size - o) {
+                let c = get!(buffer, (o - d) & (N - 1));
+                writer.write(c).map_err(LzssNintendoError::WriteError)?;
+                set!(buffer, o & (N - 1), c);
+                o += 1;
+            }
+        }
+        mask >>= 1;
+    }
+    Ok(())
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use crate::nintendo::{LzssLz10, LzssLz11, LzssNintendoError};
+    use crate::slice::SliceReader;
+    use crate::vec::VecWriter;
+    #[cfg(not(feature = "std"))]
+    use alloc::vec::Vec;
+
+    // Check that a displacement of 1 is never used.
+    fn check_vram_safe(compressed: &[u8], lz11: bool) {
+        let mut i = 4;
+        while i < compressed.len() {
+            let flags = compressed[i];
+            i += 1;
+            for bit in (0..8).rev() {
+                if i >= compressed.len() {
+                    break;
+                }
+                if flags & (1 << bit) == 0 {
+                    i += 1;
+                } else {
+                    let len = if lz11 {
+                        match compressed[i] >> 4 {
+                            0 => 3,
+                            1 => 4,
+                            _ => 2,
+                        }
+                    } else {
+                        2
+                    };
+                    i += len;
+                    let d = (usize::from(compressed[i - 2] & 0x0f) << 8)
+                        | usize::from(compressed[i - 1]);
+                    assert_ne!(d, 0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_lz10() {
+        let big_test_data = include_bytes!("nintendo.rs");
+        for vram_safe in [false, true] {
+            let compressed = if vram_safe {
+                LzssLz10::compress_vram_safe(big_test_data, VecWriter::with_capacity(0))
+            } else {
+                LzssLz10::compress(big_test_data, VecWriter::with_capacity(0))
+            }
+            .unwrap();
+            if vram_safe {
+                check_vram_safe(&compressed, false);
+            }
+            let decompressed = LzssLz10::decompress_heap(
+                SliceReader::new(&compressed),
+                VecWriter::with_capacity(big_test_data.len()),
+            )
+            .unwrap();
+            assert_eq!(decompressed.as_slice(), big_test_data);
+            assert_eq!(
+                LzssLz11::decompress_heap(
+                    SliceReader::new(&compressed),
+                    VecWriter::with_capacity(0)
+                ),
+                Err(LzssNintendoError::InvalidHeader)
+            );
+        }
+    }
+
+    #[test]
+    fn test_lz11() {
+        let mut test_data = Vec::from(&include_bytes!("nintendo.rs")[..]);
+        test_data.resize(test_data.len() + 100_000, 0);
+        test_data.extend_from_slice(&[1; 500]);
+        test_data.extend_from_slice(&[0, 1].repeat(100));
+        for vram_safe in [false, true] {
+            let compressed = if vram_safe {
+                LzssLz11::compress_vram_safe(&test_data, VecWriter::with_capacity(0))
+            } else {
+                LzssLz11::compress(&test_data, VecWriter::with_capacity(0))
+            }
+            .unwrap();
+            if vram_safe {
+                check_vram_safe(&compressed, true);
+            }
+            let decompressed = LzssLz11::decompress_heap(
+                SliceReader::new(&compressed),
+                VecWriter::with_capacity(test_data.len()),
+            )
+            .unwrap();
+            assert_eq!(decompressed, test_data);
+        }
+    }
+
+    #[test]
+    fn test_vram_safe_start() {
+        // a run at the start must not be a match with the displacement 1
+        for input in [&b"aaaaaaaa"[..], b"abababab", b"a", b"aa", b"aaa"] {
+            let compressed =
+                LzssLz10::compress_vram_safe(input, VecWriter::with_capacity(0)).unwrap();
+            check_vram_safe(&compressed, false);
+            let decompressed = LzssLz10::decompress_heap(
+                SliceReader::new(&compressed),
+                VecWriter::with_capacity(0),
+            )
+            .unwrap();
+            assert_eq!(decompressed, input);
+            let compressed =
+                LzssLz11::compress_vram_safe(input, VecWriter::with_capacity(0)).unwrap();
+            check_vram_safe(&compressed, true);
+            let decompressed = LzssLz11::decompress_heap(
+                SliceReader::new(&compressed),
+                VecWriter::with_capacity(0),
+            )
+            .unwrap();
+            assert_eq!(decompressed, input);
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            LzssLz10::decompress_heap(
+                SliceReader::new(&[0x10, 9, 0, 0, 0x10, b'a', b'b', b'c', 0x30]),
+                VecWriter::with_capacity(0)
+            ),
+            Err(LzssNintendoError::Truncated)
+        );
+        assert_eq!(
+            LzssLz10::decompress_heap(
+                SliceReader::new(&[0x10, 9, 0, 0, 0x10, b'a', b'b', b'c', 0x30, 0x03]),
+                VecWriter::with_capacity(0)
+            ),
+            Err(LzssNintendoError::InvalidDisplacement)
+        );
+    }
+}
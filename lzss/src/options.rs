@@ -0,0 +1,198 @@
+use crate::dynamic::LzssDynError;
+use core::ops::BitOr;
+
+/// Options which change the format of the compressed stream.
+///
+/// Streams created with options can only be decompressed with the same options,
+/// without options (the default) the format is unchanged.
+///
+/// The options are set with [`LzssDyn::with_options`](crate::LzssDyn::with_options) or the
+/// `O` parameter of [`Lzss`](crate::Lzss) (see [`LzssOptions::bits`]).
+///
+/// They are supported by all functions of [`LzssDyn`](crate::LzssDyn) and [`Lzss`](crate::Lzss)
+/// (and [`LzssCodec`](crate::LzssCodec)) and the [`LzssCompressor`](crate::LzssCompressor) and
+/// [`LzssDecompressor`](crate::LzssDecompressor). Only the other formats (`compress_delta`,
+/// `compress_words` and `compress_huffman`) assert that there are no options.
+///
+/// ```rust
+/// # use lzss::{lzss_type, LzssDyn, LzssDynError, LzssOptions};
+/// type MyLzss = lzss_type!(10, 4, 0x20, 0, LzssOptions::RELATIVE);
+/// let my_lzss = LzssDyn::new(10, 4, 0x20)?.with_options(LzssOptions::RELATIVE);
+/// assert_eq!(MyLzss::as_dyn(), my_lzss);
+/// # Ok::<(), LzssDynError>(())
+/// ```
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "u8", into = "u8"))]
+pub struct LzssOptions(u8);
+
+impl LzssOptions {
+    /// No options, the original format.
+    pub const NONE: LzssOptions = LzssOptions(0);
+
+    /// Matches contain the distance back from the current position (minus one) instead of
+    /// the absolute position in the ring buffer.
+    ///
+    /// The buffer in front of the input (filled with `C`) is never referenced, thus the
+    /// stream is independent of the initial buffer. If the compressor limits the distance
+    /// (see [`LzssDyn::compress_with_max_distance`](crate::LzssDyn::compress_with_max_distance))
+    /// a smaller window is enough for decompression
+    /// (see [`LzssDyn::decompress_with_window`](crate::LzssDyn::decompress_with_window)).
+    pub const RELATIVE: LzssOptions = LzssOptions(1);
+
+    /// The end of the stream is marked with a reserved match, the decompression stops there.
+    ///
+    /// The reserved match has the position of the byte which would be written next (or the
+    /// largest distance of the current offset width with [`LzssOptions::RELATIVE`] or
+    /// [`LzssOptions::ADAPTIVE_OFFSET`]), which is otherwise never used.
+    /// It allows to embed a stream without knowing its length and without an end of the input.
+    ///
+    /// The length of the end marker is `0`, with the length `1` it's a flush marker
+    /// (see [`LzssCompressor::flush`](crate::LzssCompressor::flush)) and the rest of the
+    /// byte is skipped. Other lengths are never written and end the stream as well.
+    pub const END_MARKER: LzssOptions = LzssOptions(2);
+
+    /// A match with the maximal length (`F`) is followed by continuation bytes, each is added to
+    /// the length and `255` means that another one follows.
+    ///
+    /// Thus long repeats (like zero-filled regions) are a single match of up to about
+    /// `1 << EI` bytes (the look-ahead of the compressor), instead of one match every `F` bytes.
+    pub const EXTENDED_LENGTH: LzssOptions = LzssOptions(4);
+
+    /// The width of the offset grows with the amount of data seen so far, up to `EI` bits,
+    /// the distances are relative (as with [`LzssOptions::RELATIVE`]).
+    ///
+    /// At the start of a stream only a few bits per match are used, which helps small inputs.
+    /// The flag is always followed by a literal of 8 bits or a match of at least 7 bits.
+    pub const ADAPTIVE_OFFSET: LzssOptions = LzssOptions(8);
+
+    const ALL: u8 = 15;
+
+    /// Get the options as bits, e.g. for the `O` parameter of [`Lzss`](crate::Lzss).
+    #[inline(always)]
+    #[must_use]
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// Create options from bits, `None` is returned if unknown bits are set.
+    #[inline(always)]
+    #[must_use]
+    pub const fn from_bits(bits: u8) -> Option<Self> {
+        if bits & !Self::ALL == 0 {
+            Some(LzssOptions(bits))
+        } else {
+            None
+        }
+    }
+
+    /// Returns `true` if all options in `other` are set.
+    #[inline(always)]
+    #[must_use]
+    pub const fn contains(self, other: LzssOptions) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns `true` if no option is set.
+    #[inline(always)]
+    #[must_use]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// The combination of both options.
+    #[inline(always)]
+    #[must_use]
+    pub const fn union(self, other: LzssOptions) -> Self {
+        LzssOptions(self.0 | other.0)
+    }
+
+    // Matches contain a distance, see RELATIVE and ADAPTIVE_OFFSET.
+    #[inline(always)]
+    pub(crate) const fn is_relative(self) -> bool {
+        self.contains(Self::RELATIVE) || self.contains(Self::ADAPTIVE_OFFSET)
+    }
+
+    // The width of the offset after `seen` bytes: EI without ADAPTIVE_OFFSET, otherwise
+    // enough for all distances seen so far plus the marker, but a match is at least 8 bits
+    // long, thus the padding at the end of a stream is never read as a match.
+    #[inline(always)]
+    pub(crate) const fn offset_bits(self, ei: usize, ej: usize, seen: usize) -> usize {
+        if !self.contains(Self::ADAPTIVE_OFFSET) {
+            return ei;
+        }
+        let max_distance = if seen < (1 << ei) {
+            seen
+        } else {
+            (1 << ei) - 1
+        };
+        let bits = (usize::BITS - max_distance.leading_zeros()) as usize;
+        let min_bits = 7usize.saturating_sub(ej);
+        if bits < min_bits {
+            min_bits
+        } else {
+            bits
+        }
+    }
+
+    // The names used by FromStr and Display of LzssDyn.
+    pub(crate) const NAMES: [(LzssOptions, &'static str); 4] = [
+        (Self::RELATIVE, "relative"),
+        (Self::END_MARKER, "end-marker"),
+        (Self::EXTENDED_LENGTH, "extended-length"),
+        (Self::ADAPTIVE_OFFSET, "adaptive-offset"),
+    ];
+}
+
+impl BitOr for LzssOptions {
+    type Output = LzssOptions;
+
+    #[inline(always)]
+    fn bitor(self, rhs: Self) -> Self::Output {
+        self.union(rhs)
+    }
+}
+
+impl From<LzssOptions> for u8 {
+    #[inline(always)]
+    fn from(value: LzssOptions) -> Self {
+        value.0
+    }
+}
+
+impl TryFrom<u8> for LzssOptions {
+    type Error = LzssDynError;
+
+    #[inline(always)]
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        LzssOptions::from_bits(value).ok_or(LzssDynError::UnknownOptions)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use crate::options::LzssOptions;
+
+    #[test]
+    fn test_bits() {
+        assert_eq!(LzssOptions::default(), LzssOptions::NONE);
+        assert!(LzssOptions::NONE.is_empty());
+        assert!(!LzssOptions::RELATIVE.is_empty());
+        assert!(LzssOptions::RELATIVE.contains(LzssOptions::NONE));
+        assert!(!LzssOptions::NONE.contains(LzssOptions::RELATIVE));
+        assert_eq!(
+            LzssOptions::from_bits(LzssOptions::RELATIVE.bits()),
+            Some(LzssOptions::RELATIVE)
+        );
+        assert_eq!(LzssOptions::from_bits(0x80), None);
+        assert!(LzssOptions::try_from(0x80).is_err());
+        assert_eq!(
+            LzssOptions::NONE | LzssOptions::RELATIVE,
+            LzssOptions::RELATIVE
+        );
+        let both = LzssOptions::RELATIVE | LzssOptions::END_MARKER;
+        assert!(both.contains(LzssOptions::RELATIVE));
+        assert!(both.contains(LzssOptions::END_MARKER));
+        assert!(!LzssOptions::RELATIVE.contains(both));
+    }
+}
@@ -34,3 +34,34 @@ pub trait Write {
     /// Be aware that `finish` is not called when an error occurred.
     fn finish(self) -> Result<Self::Output, Self::Error>;
 }
+
+/// Trait for reading bytes at any position, e.g. the reference for
+/// [`LzssDyn::decompress_delta`](crate::LzssDyn::decompress_delta).
+///
+/// It is implemented for slices, other sources (like a flash memory) can implement it as well.
+///
+/// ```rust
+/// # use lzss::ReadAt;
+/// let mut data: &[u8] = &[10, 42];
+/// assert_eq!(data.read_at(1), Ok(Some(42)));
+/// assert_eq!(data.read_at(2), Ok(None));
+/// ```
+pub trait ReadAt {
+    /// The error which can happen during a read operation.
+    ///
+    /// Use [Void](void::Void) when no error can be emitted.
+    type Error;
+    /// Read the byte at the position.
+    ///
+    /// Return `Ok(None)` if the position is beyond the end.
+    fn read_at(&mut self, position: usize) -> Result<Option<u8>, Self::Error>;
+}
+
+impl ReadAt for &[u8] {
+    /// No error can occur.
+    type Error = void::Void;
+    #[inline(always)]
+    fn read_at(&mut self, position: usize) -> Result<Option<u8>, Self::Error> {
+        Ok(self.get(position).copied())
+    }
+}
@@ -52,6 +52,68 @@ fn dynamic() {
                 &decoded[..],
                 "LzssDyn<{ei},{ej},0x{INIT_BYTE:02x}> Data mismatch"
             );
+            let decoded_vec = lzss
+                .decompress_to_vec(SliceReader::new(&encoded))
+                .void_unwrap();
+            assert_eq!(
+                EXAMPLE_DATA,
+                &decoded_vec[..],
+                "LzssDyn<{ei},{ej},0x{INIT_BYTE:02x}> Data mismatch (to_vec)"
+            );
+            let encoded_ring = lzss
+                .compress_ring(
+                    SliceReader::new(EXAMPLE_DATA),
+                    VecWriter::with_capacity(EXAMPLE_DATA.len()),
+                )
+                .void_unwrap();
+            assert_eq!(
+                encoded, encoded_ring,
+                "LzssDyn<{ei},{ej},0x{INIT_BYTE:02x}> Compressed mismatch (ring)"
+            );
+            let encoded_slice = lzss
+                .compress_slice(EXAMPLE_DATA, VecWriter::with_capacity(EXAMPLE_DATA.len()))
+                .void_unwrap();
+            assert_eq!(
+                encoded, encoded_slice,
+                "LzssDyn<{ei},{ej},0x{INIT_BYTE:02x}> Compressed mismatch (slice)"
+            );
+        }
+    }
+}
+
+#[test]
+#[ignore]
+fn dynamic_p() {
+    debug_assert!(false, "Disabled in debug mode");
+    for (ei, ej) in [(5, 3), (10, 4), (12, 4), (13, 5)] {
+        for p in 0..=32 {
+            // p = 0 selects the default
+            let is_valid = p == 0 || (1 + ei + ej <= 9 * (p + 1) && (1 << ej) + p < 1 << ei);
+            let params = LzssDyn::new_with_p(ei, ej, INIT_BYTE, p);
+            assert_eq!(
+                is_valid,
+                params.is_ok(),
+                "LzssDyn<{ei},{ej},0x{INIT_BYTE:02x},{p}>::new_with_p returned the wrong case"
+            );
+            if let Ok(lzss) = params {
+                let encoded = lzss
+                    .compress(
+                        SliceReader::new(EXAMPLE_DATA),
+                        VecWriter::with_capacity(EXAMPLE_DATA.len()),
+                    )
+                    .void_unwrap();
+                let decoded = lzss
+                    .decompress(
+                        SliceReader::new(&encoded),
+                        VecWriter::with_capacity(EXAMPLE_DATA.len()),
+                    )
+                    .void_unwrap();
+                assert_eq!(
+                    EXAMPLE_DATA,
+                    &decoded[..],
+                    "LzssDyn<{ei},{ej},0x{INIT_BYTE:02x},{p}> Data mismatch"
+                );
+            }
         }
     }
 }
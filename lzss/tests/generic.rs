@@ -6,13 +6,13 @@ use lzss::{Lzss, ResultLzssErrorVoidExt, SliceReader, VecWriter};
 mod common;
 
 macro_rules! test_generic {
-    ($name:ident, $ei:expr, $ej:expr) => {
+    ($name:ident, $ei:expr, $ej:expr $(, $p:expr)?) => {
         #[test]
         #[ignore]
         fn $name() {
             debug_assert!(false, "Disabled in debug mode");
 
-            type MyLzss = Lzss<$ei, $ej, INIT_BYTE, { 1 << $ei }, { 2 << $ei }>;
+            type MyLzss = Lzss<$ei, $ej, INIT_BYTE, { 1 << $ei }, { 2 << $ei } $(, $p)?>;
 
             // compress and decompress
             let compressed = MyLzss::compress_heap(
@@ -29,6 +29,11 @@ macro_rules! test_generic {
             // check if the decompressed matches the original
             assert_eq!(EXAMPLE_DATA, &decompressed[..]);
 
+            // decompress without a buffer
+            let decompressed_vec =
+                MyLzss::decompress_to_vec(SliceReader::new(&compressed)).void_unwrap();
+            assert_eq!(EXAMPLE_DATA, &decompressed_vec[..]);
+
             // compress via dyn and check if the compressed is identical
             let compressed_dyn = MyLzss::as_dyn()
                 .compress(
@@ -37,6 +42,20 @@ macro_rules! test_generic {
                 )
                 .void_unwrap();
             assert_eq!(compressed, compressed_dyn);
+
+            // compress with a ring buffer and check if the compressed is identical
+            let compressed_ring = MyLzss::compress_ring_heap(
+                SliceReader::new(EXAMPLE_DATA),
+                VecWriter::with_capacity(EXAMPLE_DATA.len()),
+            )
+            .void_unwrap();
+            assert_eq!(compressed, compressed_ring);
+
+            // compress from a slice and check if the compressed is identical
+            let compressed_slice =
+                MyLzss::compress_slice(EXAMPLE_DATA, VecWriter::with_capacity(EXAMPLE_DATA.len()))
+                    .void_unwrap();
+            assert_eq!(compressed, compressed_slice);
         }
     };
 }
@@ -51,3 +70,7 @@ test_generic!(generic_5_3, 5, 3);
 test_generic!(generic_6_2, 6, 2);
 test_generic!(generic_13_11, 13, 11);
 test_generic!(generic_23_1, 23, 1);
+// "custom p"
+test_generic!(generic_12_4_2, 12, 4, 2);
+test_generic!(generic_10_4_5, 10, 4, 5);
+test_generic!(generic_5_3_23, 5, 3, 23);
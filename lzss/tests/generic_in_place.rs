@@ -1,5 +1,8 @@
 use common::{EXAMPLE_DATA, INIT_BYTE};
-use lzss::{Lzss, ResultLzssErrorVoidReadExt, SliceReader, SliceWriter};
+use lzss::{
+    Lzss, LzssOptions, ResultLzssErrorVoidExt, ResultLzssErrorVoidReadExt, SliceReader,
+    SliceWriteError, SliceWriter, VecWriter,
+};
 
 mod common;
 
@@ -33,6 +36,23 @@ fn compress_in_place() {
                         "LZSS::compress_in_place(offset={offset}): decompressed size error"
                     );
                     decompressed[decompressed_len..].copy_from_slice(&compressed[new_offset..]);
+                    // continue the compression and check if the combined stream decompresses
+                    let continued = MyLzss::compress_in_place_continue_stack(
+                        compressed,
+                        compressed_len,
+                        new_offset,
+                        VecWriter::with_capacity(EXAMPLE_DATA.len()),
+                    )
+                    .void_unwrap();
+                    let mut combined = compressed[0..compressed_len.saturating_sub(1)].to_vec();
+                    combined.extend(continued);
+                    let decompressed_combined =
+                        MyLzss::decompress_to_vec(SliceReader::new(&combined)).void_unwrap();
+                    assert_eq!(
+                        &decompressed_combined[..],
+                        EXAMPLE_DATA,
+                        "LZSS::compress_in_place_continue_stack(offset={offset}): decompressed data does not match"
+                    );
                 }
                 // check if the data matches
                 assert_eq!(
@@ -47,3 +67,86 @@ fn compress_in_place() {
         }
     }
 }
+
+#[test]
+fn compress_in_place_continue_long_tokens() {
+    // a match is 25 bits long, and some offsets stop the first pass on a byte boundary
+    const EI: usize = 16;
+    const EJ: usize = 8;
+    type MyLzss =
+        Lzss<EI, EJ, INIT_BYTE, { 1 << EI }, { 2 << EI }, 0, { LzssOptions::RELATIVE.bits() }>;
+
+    // the example data and the same with the high bit set
+    let high: Vec<u8> = EXAMPLE_DATA.iter().map(|&b| b | 0x80).collect();
+    let mut buffer = vec![[0; 2 << EI]; 1];
+    for data in [&EXAMPLE_DATA[..], &high[..]] {
+        for offset in MyLzss::MIN_OFFSET..MyLzss::MIN_OFFSET + data.len() {
+            let mut io = vec![!INIT_BYTE; offset + data.len()];
+            io[offset..].copy_from_slice(data);
+            let (compressed_len, new_offset) = MyLzss::compress_in_place(&mut io, offset);
+            let mut combined = io[0..compressed_len].to_vec();
+            if let Some(new_offset) = new_offset {
+                combined.truncate(compressed_len.saturating_sub(1));
+                combined.extend(
+                    MyLzss::compress_in_place_continue_with_buffer(
+                        &io,
+                        compressed_len,
+                        new_offset,
+                        VecWriter::with_capacity(data.len()),
+                        &mut buffer[0],
+                    )
+                    .void_unwrap(),
+                );
+            }
+            assert_eq!(
+                MyLzss::decompress_to_vec(SliceReader::new(&combined)).void_unwrap(),
+                data,
+                "LZSS::compress_in_place_continue_with_buffer(offset={offset}): decompressed data does not match"
+            );
+        }
+    }
+}
+
+#[test]
+fn decompress_in_place() {
+    const EI: usize = 8;
+    const EJ: usize = 4;
+    type MyLzss = Lzss<EI, EJ, INIT_BYTE, { 1 << EI }, { 2 << EI }>;
+
+    // the example data and some hardly compressible data
+    let mut random = Vec::with_capacity(1000);
+    let mut x: u32 = 1;
+    for _ in 0..1000 {
+        x = x.wrapping_mul(1_103_515_245).wrapping_add(12345);
+        random.push((x >> 16) as u8);
+    }
+    for data in [&EXAMPLE_DATA[..], &random[..]] {
+        let mut compressed = vec![0; 2 * data.len()];
+        let compressed_len =
+            MyLzss::compress_stack(SliceReader::new(data), SliceWriter::new(&mut compressed))
+                .void_read_unwrap()
+                .unwrap();
+        let compressed = &compressed[..compressed_len];
+        let margin = MyLzss::decompress_in_place_margin(data.len());
+        for size in compressed_len..=data.len() + margin {
+            let mut io = vec![0; size];
+            let offset = size - compressed_len;
+            io[offset..].copy_from_slice(compressed);
+            match MyLzss::decompress_in_place(&mut io, offset, compressed_len) {
+                Ok(decompressed_len) => {
+                    assert_eq!(
+                        &io[..decompressed_len],
+                        data,
+                        "LZSS::decompress_in_place(size={size}): decompressed data does not match"
+                    );
+                }
+                Err(SliceWriteError) => {
+                    assert!(
+                        size < data.len() + margin,
+                        "LZSS::decompress_in_place(size={size}): failed despite the margin"
+                    );
+                }
+            }
+        }
+    }
+}